@@ -0,0 +1,171 @@
+//! Macros for [leptos-bevy-canvas](https://docs.rs/leptos-bevy-canvas).
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Field, Fields, Index};
+
+/// Derives `Diffable` for a struct. Please see the docs of `Diffable` in `leptos-bevy-canvas`.
+///
+/// Every field has to implement `Diffable` itself. Fields that don't can be annotated with
+/// `#[diffable(whole)]` in which case they are compared with `PartialEq` and sent as a whole when
+/// they changed. Enums are always diffed as a whole.
+#[proc_macro_derive(Diffable, attributes(diffable))]
+pub fn derive_diffable(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    derive(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn derive(input: DeriveInput) -> syn::Result<TokenStream2> {
+    if !input.generics.params.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.generics,
+            "Diffable can't be derived for generic types",
+        ));
+    }
+
+    let krate = quote! { ::leptos_bevy_canvas::prelude };
+    let name = &input.ident;
+
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        Data::Enum(_) => {
+            return Ok(quote! {
+                impl #krate::Diffable for #name {
+                    type Patch = Self;
+
+                    fn diff(&self, old: &Self) -> Option<Self::Patch> {
+                        (self != old).then(|| self.clone())
+                    }
+
+                    fn apply_patch(&mut self, patch: Self::Patch) -> Result<(), #krate::PatchError> {
+                        *self = patch;
+                        Ok(())
+                    }
+                }
+            });
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new_spanned(
+                &input.ident,
+                "Diffable can't be derived for unions",
+            ));
+        }
+    };
+
+    let vis = &input.vis;
+    let patch_name = format_ident!("{name}Patch");
+    let patch_doc = format!("Patch for [`{name}`]. Fields that didn't change are `None`.");
+
+    let mut members = Vec::with_capacity(fields.len());
+    let mut patch_types = Vec::with_capacity(fields.len());
+    let mut diffs = Vec::with_capacity(fields.len());
+    let mut applies = Vec::with_capacity(fields.len());
+
+    for (index, field) in fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => quote! { #ident },
+            None => {
+                let index = Index::from(index);
+                quote! { #index }
+            }
+        };
+        let ty = &field.ty;
+
+        if is_whole(field)? {
+            patch_types.push(quote! { #ty });
+            diffs.push(quote! {
+                (self.#member != old.#member).then(|| self.#member.clone())
+            });
+            applies.push(quote! {
+                if let Some(patch) = patch.#member {
+                    self.#member = patch;
+                }
+            });
+        } else {
+            patch_types.push(quote! { <#ty as #krate::Diffable>::Patch });
+            diffs.push(quote! {
+                #krate::Diffable::diff(&self.#member, &old.#member)
+            });
+            applies.push(quote! {
+                if let Some(patch) = patch.#member {
+                    #krate::Diffable::apply_patch(&mut self.#member, patch)?;
+                }
+            });
+        }
+
+        members.push(member);
+    }
+
+    let (patch_struct, patch_value) = match fields {
+        Fields::Named(_) => (
+            quote! {
+                #vis struct #patch_name {
+                    #(pub #members: Option<#patch_types>,)*
+                }
+            },
+            quote! {
+                #patch_name {
+                    #(#members: #diffs,)*
+                }
+            },
+        ),
+        Fields::Unnamed(_) => (
+            quote! {
+                #vis struct #patch_name(#(pub Option<#patch_types>,)*);
+            },
+            quote! {
+                #patch_name(#(#diffs,)*)
+            },
+        ),
+        Fields::Unit => (
+            quote! {
+                #vis struct #patch_name;
+            },
+            quote! { #patch_name },
+        ),
+    };
+
+    Ok(quote! {
+        #[doc = #patch_doc]
+        #[derive(Clone)]
+        #patch_struct
+
+        impl #krate::Diffable for #name {
+            type Patch = #patch_name;
+
+            fn diff(&self, old: &Self) -> Option<Self::Patch> {
+                let patch = #patch_value;
+
+                (false #(|| patch.#members.is_some())*).then_some(patch)
+            }
+
+            fn apply_patch(&mut self, patch: Self::Patch) -> Result<(), #krate::PatchError> {
+                #(#applies)*
+                Ok(())
+            }
+        }
+    })
+}
+
+fn is_whole(field: &Field) -> syn::Result<bool> {
+    let mut whole = false;
+
+    for attr in &field.attrs {
+        if attr.path().is_ident("diffable") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("whole") {
+                    whole = true;
+                    Ok(())
+                } else {
+                    Err(meta.error("expected `whole`"))
+                }
+            })?;
+        }
+    }
+
+    Ok(whole)
+}
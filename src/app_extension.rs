@@ -1,10 +1,28 @@
-use crate::prelude::{BevyQueryDuplex, QueryDataOwned};
+use crate::history::*;
+#[cfg(feature = "persist")]
+use crate::persist::*;
+use crate::prelude::{
+    BevyMessageDuplex, BevyQueryDuplex, Diffable, MessageOptions, QueryDataOwned, SyncOptions,
+    SyncUpdate, ToLeptos, Validation,
+};
+#[cfg(feature = "record")]
+use crate::record::*;
+use crate::sync_options::SyncFeature;
 use crate::systems::*;
 use crate::traits::{HasReceiver, HasSender};
 use bevy::ecs::query::QueryFilter;
+use bevy::ecs::schedule::IntoScheduleConfigs;
+use bevy::ecs::system::ScheduleSystem;
 use bevy::prelude::*;
 
 /// Adds synchronization methods to the Bevy app
+///
+/// The bridges are torn down automatically when the reactive owner that created their Leptos
+/// side (e.g. the component that called `signal_synced`) is disposed: the systems stop and the
+/// bridge resource (like the `BevyMessageDuplex`) is removed. To attach a new Leptos signal
+/// later, send its bridge resource over the channel passed to
+/// [`LeptosBevyApp::attach_bridges_from_leptos`]. It is synced just like at the start: the
+/// initial value of a synced signal is applied to Bevy and exported values are sent right away.
 pub trait LeptosBevyApp {
     /// Imports a message from Leptos into the Bevy app. Takes the Bevy message receiver as argument.
     fn import_message_from_leptos<R, M>(&mut self, bevy_rx: R) -> &mut Self
@@ -12,24 +30,206 @@ pub trait LeptosBevyApp {
         M: Message,
         R: HasReceiver<M> + Resource;
 
+    /// Version of [`LeptosBevyApp::import_message_from_leptos`] that takes `MessageOptions`.
+    fn import_message_from_leptos_with_options<R, M>(
+        &mut self,
+        bevy_rx: R,
+        options: MessageOptions,
+    ) -> &mut Self
+    where
+        M: Message,
+        R: HasReceiver<M> + Resource;
+
     /// Exports an message from Bevy to Leptos. Takes the Bevy message sender as argument.
     fn export_message_to_leptos<S, M>(&mut self, bevy_tx: S) -> &mut Self
     where
         M: Message + Clone,
         S: HasSender<M> + Resource;
 
+    /// Version of [`LeptosBevyApp::export_message_to_leptos`] that takes `MessageOptions`.
+    fn export_message_to_leptos_with_options<S, M>(
+        &mut self,
+        bevy_tx: S,
+        options: MessageOptions,
+    ) -> &mut Self
+    where
+        M: Message + Clone,
+        S: HasSender<M> + Resource;
+
+    /// Same as [`LeptosBevyApp::export_message_to_leptos`] but the messages are moved to Leptos
+    /// instead of cloned so they don't have to implement `Clone`.
+    ///
+    /// Write the messages wrapped in [`ToLeptos`] which are drained in the export schedule
+    /// (`PostUpdate` by default). Messages of type `M` itself aren't touched so none of their
+    /// readers miss one.
+    fn export_owned_message_to_leptos<S, M>(&mut self, bevy_tx: S) -> &mut Self
+    where
+        M: Send + Sync + 'static,
+        S: HasSender<M> + Resource;
+
+    /// Version of [`LeptosBevyApp::export_owned_message_to_leptos`] that takes `MessageOptions`.
+    fn export_owned_message_to_leptos_with_options<S, M>(
+        &mut self,
+        bevy_tx: S,
+        options: MessageOptions,
+    ) -> &mut Self
+    where
+        M: Send + Sync + 'static,
+        S: HasSender<M> + Resource;
+
     /// Adds duplex message handling between Bevy and Leptos. Takes the Bevy message receiver/sender as argument.
     fn add_duplex_leptos_message<D, M>(&mut self, bevy_duplex: D) -> &mut Self
     where
         M: Message + Clone,
         D: HasReceiver<M> + HasSender<M> + Resource;
 
+    /// Version of [`LeptosBevyApp::add_duplex_leptos_message`] that takes `MessageOptions`.
+    fn add_duplex_leptos_message_with_options<D, M>(
+        &mut self,
+        bevy_duplex: D,
+        options: MessageOptions,
+    ) -> &mut Self
+    where
+        M: Message + Clone,
+        D: HasReceiver<M> + HasSender<M> + Resource;
+
     /// Adds resource syncing between Bevy and Leptos. Takes the Bevy resource receiver/sender as argument.
     fn sync_leptos_signal_with_resource<D, R>(&mut self, bevy_duplex: D) -> &mut Self
     where
         R: Resource + Clone,
         D: HasReceiver<R> + HasSender<R> + Resource;
 
+    /// Version of [`LeptosBevyApp::sync_leptos_signal_with_resource`] that takes `SyncOptions`.
+    /// All the options apply.
+    fn sync_leptos_signal_with_resource_with_options<D, R>(
+        &mut self,
+        bevy_duplex: D,
+        options: SyncOptions<R>,
+    ) -> &mut Self
+    where
+        R: Resource + Clone,
+        D: HasReceiver<R> + HasSender<R> + Resource;
+
+    /// Adds syncing between an optional Bevy resource and a Leptos signal of `Option<R>`. Takes the
+    /// Bevy resource receiver/sender as argument.
+    ///
+    /// Setting the signal to `Some` inserts the resource and setting it to `None` removes it.
+    /// Inserting and removing the resource in Bevy is reflected in the signal in the same way.
+    /// If the initial value of the signal is `None` an already existing resource is kept.
+    fn sync_leptos_signal_with_optional_resource<D, R>(&mut self, bevy_duplex: D) -> &mut Self
+    where
+        R: Resource + Clone,
+        D: HasReceiver<Option<R>> + HasSender<Option<R>> + Resource;
+
+    /// Version of [`LeptosBevyApp::sync_leptos_signal_with_optional_resource`] that takes
+    /// `SyncOptions`. Only the schedules, the run conditions and `SyncOptions::record` apply.
+    /// Adding the sync panics if another option is set.
+    fn sync_leptos_signal_with_optional_resource_with_options<D, R>(
+        &mut self,
+        bevy_duplex: D,
+        options: SyncOptions<Option<R>>,
+    ) -> &mut Self
+    where
+        R: Resource + Clone,
+        D: HasReceiver<Option<R>> + HasSender<Option<R>> + Resource;
+
+    /// Syncs a single field of a resource with a Leptos signal. Takes the Bevy field
+    /// receiver/sender and two functions that project the resource to the field as arguments.
+    ///
+    /// Only the field is sent between Bevy and Leptos and only changes of the field are sent.
+    /// The resource itself doesn't have to be `Clone` but it has to exist in the Bevy app. Values
+    /// from Leptos only mark the resource as changed if they change the field.
+    ///
+    /// The bridge resource is a [`ResourceFieldSync`] that is removed when the Leptos signal is
+    /// disposed like the bridge resources of the other syncs.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use leptos_bevy_canvas::prelude::*;
+    /// #
+    /// #[derive(Resource, Default)]
+    /// struct GameSettings {
+    ///     volume: f32,
+    ///     // ...
+    /// }
+    ///
+    /// # let (volume, bevy_volume) = signal_synced(0.5);
+    /// let mut app = App::new();
+    /// app.init_resource::<GameSettings>().sync_leptos_signal_with_resource_field(
+    ///     bevy_volume,
+    ///     |settings: &GameSettings| &settings.volume,
+    ///     |settings: &mut GameSettings| &mut settings.volume,
+    /// );
+    /// ```
+    fn sync_leptos_signal_with_resource_field<D, R, F>(
+        &mut self,
+        bevy_duplex: D,
+        get: impl Fn(&R) -> &F + Send + Sync + 'static,
+        get_mut: impl Fn(&mut R) -> &mut F + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        R: Resource,
+        F: Clone + PartialEq + Send + Sync + 'static,
+        D: HasReceiver<F> + HasSender<F> + Send + Sync + 'static;
+
+    /// Version of [`LeptosBevyApp::sync_leptos_signal_with_resource_field`] that takes
+    /// `SyncOptions`. Only the schedules, the run conditions and `SyncOptions::record` apply.
+    /// Adding the sync panics if another option is set.
+    fn sync_leptos_signal_with_resource_field_with_options<D, R, F>(
+        &mut self,
+        bevy_duplex: D,
+        get: impl Fn(&R) -> &F + Send + Sync + 'static,
+        get_mut: impl Fn(&mut R) -> &mut F + Send + Sync + 'static,
+        options: SyncOptions<F>,
+    ) -> &mut Self
+    where
+        R: Resource,
+        F: Clone + PartialEq + Send + Sync + 'static,
+        D: HasReceiver<F> + HasSender<F> + Send + Sync + 'static;
+
+    /// Adds resource syncing between Bevy and Leptos that only sends the changes instead of the
+    /// whole resource. Takes the Bevy duplex returned by
+    /// [`diffed_signal_synced`](crate::prelude::diffed_signal_synced) as argument.
+    ///
+    /// This isn't part of [`LeptosBevyApp::sync_leptos_signal_with_resource`] because a different
+    /// protocol crosses the bridge: versioned [`SyncUpdate`]s instead of the resource itself.
+    /// That one works for every `Clone` resource while this one requires `Diffable` and Rust
+    /// can't choose between the two depending on whether the resource implements `Diffable`.
+    fn sync_leptos_signal_with_diffed_resource<D, R>(&mut self, bevy_duplex: D) -> &mut Self
+    where
+        R: Resource + Diffable,
+        D: HasReceiver<SyncUpdate<R>> + HasSender<SyncUpdate<R>> + Resource;
+
+    /// Version of [`LeptosBevyApp::sync_leptos_signal_with_diffed_resource`] that takes
+    /// `SyncOptions`. Only the schedules, the run conditions and `SyncOptions::record` apply.
+    /// Adding the sync panics if another option is set.
+    fn sync_leptos_signal_with_diffed_resource_with_options<D, R>(
+        &mut self,
+        bevy_duplex: D,
+        options: SyncOptions<R>,
+    ) -> &mut Self
+    where
+        R: Resource + Diffable,
+        D: HasReceiver<SyncUpdate<R>> + HasSender<SyncUpdate<R>> + Resource;
+
+    /// Exports a resource from Bevy to a read-only Leptos signal. Takes the Bevy resource sender
+    /// as argument. The resource is sent whenever it is added or changed.
+    fn export_resource_to_leptos<S, R>(&mut self, bevy_tx: S) -> &mut Self
+    where
+        R: Resource + Clone,
+        S: HasSender<R> + Resource;
+
+    /// Version of [`LeptosBevyApp::export_resource_to_leptos`] that takes `MessageOptions`. Only
+    /// [`MessageOptions::export_schedule`] applies.
+    fn export_resource_to_leptos_with_options<S, R>(
+        &mut self,
+        bevy_tx: S,
+        options: MessageOptions,
+    ) -> &mut Self
+    where
+        R: Resource + Clone,
+        S: HasSender<R> + Resource;
+
     /// Adds state syncing between Bevy and Leptos. Takes the Bevy state receiver/sender as argument.
     #[cfg(feature = "bevy_state")]
     fn sync_leptos_signal_with_state<D, S>(&mut self, bevy_duplex: D) -> &mut Self
@@ -37,11 +237,106 @@ pub trait LeptosBevyApp {
         S: bevy::state::state::FreelyMutableState + Clone,
         D: HasReceiver<S> + HasSender<S> + Resource;
 
+    /// Version of [`LeptosBevyApp::sync_leptos_signal_with_state`] that takes `SyncOptions`.
+    /// All the options except [`SyncOptions::history`] and [`SyncOptions::send_schedule`] apply.
+    /// States are synced in [`SyncOptions::schedule`] only. Adding the sync panics if a history
+    /// or a send schedule is set.
+    #[cfg(feature = "bevy_state")]
+    fn sync_leptos_signal_with_state_with_options<D, S>(
+        &mut self,
+        bevy_duplex: D,
+        options: SyncOptions<S>,
+    ) -> &mut Self
+    where
+        S: bevy::state::state::FreelyMutableState + Clone,
+        D: HasReceiver<S> + HasSender<S> + Resource;
+
+    /// Exports a state of any kind (including `ComputedStates` and `SubStates`) from Bevy to a
+    /// read-only Leptos signal. Takes the Bevy state sender returned by
+    /// [`state_signal`](crate::prelude::state_signal) as argument.
+    #[cfg(feature = "bevy_state")]
+    fn export_state_to_leptos<T, S>(&mut self, bevy_tx: T) -> &mut Self
+    where
+        S: bevy::state::state::States,
+        T: HasSender<Option<S>> + Resource;
+
+    /// Version of [`LeptosBevyApp::export_state_to_leptos`] that takes `MessageOptions`. Only
+    /// [`MessageOptions::export_schedule`] applies.
+    #[cfg(feature = "bevy_state")]
+    fn export_state_to_leptos_with_options<T, S>(
+        &mut self,
+        bevy_tx: T,
+        options: MessageOptions,
+    ) -> &mut Self
+    where
+        S: bevy::state::state::States,
+        T: HasSender<Option<S>> + Resource;
+
+    /// Exports every `StateTransitionEvent` of the state `S` to Leptos. Takes the Bevy sender
+    /// returned by `message_b2l::<StateTransitionEvent<S>>()` as argument.
+    ///
+    /// Like every message receiver the Leptos receiver only keeps the last transition when it is
+    /// read like a signal. Use
+    /// [`on_message`](crate::prelude::LeptosChannelMessageReceiver::on_message) to handle every
+    /// single transition.
+    #[cfg(feature = "bevy_state")]
+    fn export_state_transitions_to_leptos<T, S>(&mut self, bevy_tx: T) -> &mut Self
+    where
+        S: bevy::state::state::States,
+        T: HasSender<bevy::state::state::StateTransitionEvent<S>> + Resource;
+
     /// Adds query syncing between Bevy and Leptos. Takes the Bevy query duplex as argument.
     fn sync_leptos_signal_with_query<D, F>(&mut self, duplex: BevyQueryDuplex<D, F>) -> &mut Self
     where
         for<'a> D: QueryDataOwned<'a> + Send + Sync + 'static,
         F: QueryFilter + 'static;
+
+    /// Version of [`LeptosBevyApp::sync_leptos_signal_with_query`] that takes `SyncOptions`. Only
+    /// the schedules, [`SyncOptions::max_send_rate`], the run conditions and
+    /// `SyncOptions::record` apply to queries. Adding the sync panics if another option is set.
+    fn sync_leptos_signal_with_query_with_options<D, F>(
+        &mut self,
+        duplex: BevyQueryDuplex<D, F>,
+        options: SyncOptions<Option<D>>,
+    ) -> &mut Self
+    where
+        for<'a> D: QueryDataOwned<'a> + Send + Sync + 'static,
+        F: QueryFilter + 'static;
+
+    /// Attaches bridge resources that Leptos sends after the app has been built. Takes the Bevy
+    /// receiver of a channel that carries the bridges as argument.
+    ///
+    /// Use this to attach a new Leptos signal after the previous one has been disposed and its
+    /// bridge resource removed, e.g. when a component is mounted again. The sync of the bridge
+    /// has to be added as usual. A bridge that is received replaces the current one at the start
+    /// of the next frame and is synced just like at the start.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use leptos::prelude::*;
+    /// # use leptos_bevy_canvas::prelude::*;
+    /// #
+    /// #[derive(Resource, Clone)]
+    /// struct Volume(f32);
+    ///
+    /// # let owner = Owner::new();
+    /// # owner.with(|| {
+    /// let (attach_volume, bevy_attach_volume) = message_l2b::<BevyMessageDuplex<Volume>>();
+    /// let (volume, bevy_volume) = signal_synced(Volume(0.5));
+    ///
+    /// let mut app = App::new();
+    /// app.sync_leptos_signal_with_resource(bevy_volume)
+    ///     .attach_bridges_from_leptos(bevy_attach_volume);
+    ///
+    /// // later, e.g. in a component that is mounted again
+    /// let (volume, bevy_volume) = signal_synced(Volume(0.5));
+    /// attach_volume.send(bevy_volume).unwrap();
+    /// # });
+    /// ```
+    fn attach_bridges_from_leptos<R, D>(&mut self, bevy_rx: R) -> &mut Self
+    where
+        D: Resource,
+        R: HasReceiver<D> + Resource;
 }
 
 impl LeptosBevyApp for App {
@@ -50,12 +345,39 @@ impl LeptosBevyApp for App {
         M: Message,
         R: HasReceiver<M> + Resource,
     {
-        self.insert_resource(bevy_rx)
+        self.import_message_from_leptos_with_options(bevy_rx, MessageOptions::default())
+    }
+
+    fn import_message_from_leptos_with_options<R, M>(
+        &mut self,
+        bevy_rx: R,
+        options: MessageOptions,
+    ) -> &mut Self
+    where
+        M: Message,
+        R: HasReceiver<M> + Resource,
+    {
+        init_bridge(self);
+        init_receiver_teardown::<R, M>(self);
+
+        #[cfg(feature = "record")]
+        if init_recording::<R, M>(self, options.record_channel()) {
+            self.add_systems(
+                options.import_schedule_or_default(),
+                replay_leptos_messages::<R, M>
+                    .in_set(ReplayLeptosSet)
+                    .in_set(ImportLeptosMessageSet),
+            );
+        }
+
+        init_imported_message_ids::<M>(self)
+            .insert_resource(bevy_rx)
             .add_message::<M>()
-            .init_resource::<ImportedMessageIds<M>>()
             .add_systems(
-                PreUpdate,
-                import_and_send_leptos_messages::<R, M>.in_set(ImportLeptosMessageSet),
+                options.import_schedule_or_default(),
+                import_and_send_leptos_messages::<R, M>
+                    .run_if(resource_exists::<R>)
+                    .in_set(ImportLeptosMessageSet),
             )
     }
 
@@ -64,12 +386,65 @@ impl LeptosBevyApp for App {
         M: Message + Clone,
         R: HasSender<M> + Resource,
     {
-        self.insert_resource(bevy_tx)
+        self.export_message_to_leptos_with_options(bevy_tx, MessageOptions::default())
+    }
+
+    fn export_message_to_leptos_with_options<R, M>(
+        &mut self,
+        bevy_tx: R,
+        options: MessageOptions,
+    ) -> &mut Self
+    where
+        M: Message + Clone,
+        R: HasSender<M> + Resource,
+    {
+        init_bridge(self);
+        init_sender_teardown::<R, M>(self);
+
+        #[cfg(feature = "record")]
+        init_recording::<R, M>(self, options.record_channel());
+
+        init_imported_message_ids::<M>(self)
+            .insert_resource(bevy_tx)
             .add_message::<M>()
-            .init_resource::<ImportedMessageIds<M>>()
             .add_systems(
-                PostUpdate,
-                read_and_export_leptos_messages::<R, M>.in_set(ExportLeptosMessageSet),
+                options.export_schedule_or_default(),
+                read_and_export_leptos_messages::<R, M>
+                    .run_if(resource_exists::<R>)
+                    .in_set(ExportLeptosMessageSet),
+            )
+    }
+
+    fn export_owned_message_to_leptos<R, M>(&mut self, bevy_tx: R) -> &mut Self
+    where
+        M: Send + Sync + 'static,
+        R: HasSender<M> + Resource,
+    {
+        self.export_owned_message_to_leptos_with_options(bevy_tx, MessageOptions::default())
+    }
+
+    fn export_owned_message_to_leptos_with_options<R, M>(
+        &mut self,
+        bevy_tx: R,
+        options: MessageOptions,
+    ) -> &mut Self
+    where
+        M: Send + Sync + 'static,
+        R: HasSender<M> + Resource,
+    {
+        init_bridge(self);
+        init_sender_teardown::<R, M>(self);
+
+        #[cfg(feature = "record")]
+        init_recording::<R, M>(self, options.record_channel());
+
+        self.insert_resource(bevy_tx)
+            .add_message::<ToLeptos<M>>()
+            .add_systems(
+                options.export_schedule_or_default(),
+                drain_and_export_leptos_messages::<R, M>
+                    .run_if(resource_exists::<R>)
+                    .in_set(ExportLeptosMessageSet),
             )
     }
 
@@ -78,15 +453,45 @@ impl LeptosBevyApp for App {
         M: Message + Clone,
         D: HasReceiver<M> + HasSender<M> + Resource,
     {
-        self.insert_resource(bevy_duplex)
+        self.add_duplex_leptos_message_with_options(bevy_duplex, MessageOptions::default())
+    }
+
+    fn add_duplex_leptos_message_with_options<D, M>(
+        &mut self,
+        bevy_duplex: D,
+        options: MessageOptions,
+    ) -> &mut Self
+    where
+        M: Message + Clone,
+        D: HasReceiver<M> + HasSender<M> + Resource,
+    {
+        init_bridge(self);
+        init_receiver_teardown::<D, M>(self);
+
+        #[cfg(feature = "record")]
+        if init_recording::<D, M>(self, options.record_channel()) {
+            self.add_systems(
+                options.import_schedule_or_default(),
+                replay_leptos_messages::<D, M>
+                    .in_set(ReplayLeptosSet)
+                    .in_set(ImportLeptosMessageSet),
+            );
+        }
+
+        init_imported_message_ids::<M>(self)
+            .insert_resource(bevy_duplex)
             .add_message::<M>()
             .add_systems(
-                PreUpdate,
-                import_and_send_leptos_messages::<D, M>.in_set(ImportLeptosMessageSet),
+                options.import_schedule_or_default(),
+                import_and_send_leptos_messages::<D, M>
+                    .run_if(resource_exists::<D>)
+                    .in_set(ImportLeptosMessageSet),
             )
             .add_systems(
-                PostUpdate,
-                read_and_export_leptos_messages::<D, M>.in_set(ExportLeptosMessageSet),
+                options.export_schedule_or_default(),
+                read_and_export_leptos_messages::<D, M>
+                    .run_if(resource_exists::<D>)
+                    .in_set(ExportLeptosMessageSet),
             )
     }
 
@@ -95,28 +500,410 @@ impl LeptosBevyApp for App {
         R: Resource + Clone,
         D: HasReceiver<R> + HasSender<R> + Resource,
     {
+        self.sync_leptos_signal_with_resource_with_options(bevy_duplex, SyncOptions::default())
+    }
+
+    fn sync_leptos_signal_with_resource_with_options<D, R>(
+        &mut self,
+        bevy_duplex: D,
+        options: SyncOptions<R>,
+    ) -> &mut Self
+    where
+        R: Resource + Clone,
+        D: HasReceiver<R> + HasSender<R> + Resource,
+    {
+        init_bridge(self);
+
+        #[cfg(feature = "record")]
+        let recorded = init_recording::<D, R>(self, options.record_channel());
+
+        for mut message in bevy_duplex.rx().try_iter() {
+            #[cfg(feature = "record")]
+            if recorded {
+                record_initial_value::<D, R>(self, message.clone());
+            }
+
+            let valid = self.world().get_resource::<R>().is_none_or(|current| {
+                options.validate(current, &mut message) != Validation::Invalid
+            });
+
+            if valid {
+                self.insert_resource(message);
+            }
+        }
+
+        #[cfg(feature = "persist")]
+        if options.persistence().is_some() {
+            let current = self.world().get_resource::<R>();
+            if let Some(value) = load_persisted(&options, &bevy_duplex, current) {
+                self.insert_resource(value);
+            }
+
+            self.add_systems(Last, persist_resource::<R>.in_set(PersistSet));
+        }
+
+        if let Some((bevy_history, depth)) = options.history_channel() {
+            self.insert_resource(History::<R>::new(depth).with_channel(bevy_history))
+                .add_systems(
+                    options.schedule_or(PreUpdate),
+                    update_history::<R>
+                        .in_set(HistorySet)
+                        .after(SyncSignalResourceSet),
+                );
+        }
+
+        add_sync_systems::<D, (), _, _, _>(
+            self,
+            &options,
+            apply_signal_resource::<D, R>,
+            send_signal_resource::<D, R>,
+            SyncSignalResourceSet,
+        );
+
+        #[cfg(feature = "record")]
+        if recorded {
+            add_replay_system(
+                self,
+                &options,
+                replay_leptos_resource::<D, R>,
+                SyncSignalResourceSet,
+            );
+        }
+
+        init_receiver_teardown::<D, R>(self);
+        init_frame_end_tick(self)
+            .insert_resource(options)
+            .insert_resource(bevy_duplex)
+    }
+
+    fn sync_leptos_signal_with_optional_resource<D, R>(&mut self, bevy_duplex: D) -> &mut Self
+    where
+        R: Resource + Clone,
+        D: HasReceiver<Option<R>> + HasSender<Option<R>> + Resource,
+    {
+        self.sync_leptos_signal_with_optional_resource_with_options(
+            bevy_duplex,
+            SyncOptions::default(),
+        )
+    }
+
+    fn sync_leptos_signal_with_optional_resource_with_options<D, R>(
+        &mut self,
+        bevy_duplex: D,
+        options: SyncOptions<Option<R>>,
+    ) -> &mut Self
+    where
+        R: Resource + Clone,
+        D: HasReceiver<Option<R>> + HasSender<Option<R>> + Resource,
+    {
+        options.assert_supported(
+            "sync_leptos_signal_with_optional_resource",
+            &[SyncFeature::SendSchedule],
+        );
+
+        init_bridge(self);
+
+        #[cfg(feature = "record")]
+        let recorded = init_recording::<D, Option<R>>(self, options.record_channel());
+
         for message in bevy_duplex.rx().try_iter() {
+            #[cfg(feature = "record")]
+            if recorded {
+                record_initial_value::<D, Option<R>>(self, message.clone());
+            }
+
+            // `None` keeps an already existing resource
+            let Some(message) = message else {
+                continue;
+            };
+
             self.insert_resource(message);
         }
 
-        self.insert_resource(bevy_duplex).add_systems(
-            Update,
-            sync_signal_resource::<D, R>.in_set(SyncSignalResourceSet),
+        add_sync_systems::<D, OptionalResourceSync, _, _, _>(
+            self,
+            &options,
+            apply_signal_optional_resource::<D, R>,
+            send_signal_optional_resource::<D, R>,
+            SyncSignalResourceSet,
+        );
+
+        #[cfg(feature = "record")]
+        if recorded {
+            add_replay_system(
+                self,
+                &options,
+                replay_leptos_optional_resource::<D, R>,
+                SyncSignalResourceSet,
+            );
+        }
+
+        init_receiver_teardown::<D, Option<R>>(self).insert_resource(bevy_duplex)
+    }
+
+    fn sync_leptos_signal_with_resource_field<D, R, F>(
+        &mut self,
+        bevy_duplex: D,
+        get: impl Fn(&R) -> &F + Send + Sync + 'static,
+        get_mut: impl Fn(&mut R) -> &mut F + Send + Sync + 'static,
+    ) -> &mut Self
+    where
+        R: Resource,
+        F: Clone + PartialEq + Send + Sync + 'static,
+        D: HasReceiver<F> + HasSender<F> + Send + Sync + 'static,
+    {
+        self.sync_leptos_signal_with_resource_field_with_options(
+            bevy_duplex,
+            get,
+            get_mut,
+            SyncOptions::default(),
+        )
+    }
+
+    fn sync_leptos_signal_with_resource_field_with_options<D, R, F>(
+        &mut self,
+        bevy_duplex: D,
+        get: impl Fn(&R) -> &F + Send + Sync + 'static,
+        get_mut: impl Fn(&mut R) -> &mut F + Send + Sync + 'static,
+        options: SyncOptions<F>,
+    ) -> &mut Self
+    where
+        R: Resource,
+        F: Clone + PartialEq + Send + Sync + 'static,
+        D: HasReceiver<F> + HasSender<F> + Send + Sync + 'static,
+    {
+        add_resource_field_sync(
+            self,
+            ResourceFieldSync::new(bevy_duplex, get, get_mut),
+            options,
+        )
+    }
+
+    fn sync_leptos_signal_with_diffed_resource<D, R>(&mut self, bevy_duplex: D) -> &mut Self
+    where
+        R: Resource + Diffable,
+        D: HasReceiver<SyncUpdate<R>> + HasSender<SyncUpdate<R>> + Resource,
+    {
+        self.sync_leptos_signal_with_diffed_resource_with_options(
+            bevy_duplex,
+            SyncOptions::default(),
         )
     }
 
+    fn sync_leptos_signal_with_diffed_resource_with_options<D, R>(
+        &mut self,
+        bevy_duplex: D,
+        options: SyncOptions<R>,
+    ) -> &mut Self
+    where
+        R: Resource + Diffable,
+        D: HasReceiver<SyncUpdate<R>> + HasSender<SyncUpdate<R>> + Resource,
+    {
+        options.assert_supported(
+            "sync_leptos_signal_with_diffed_resource",
+            &[SyncFeature::SendSchedule],
+        );
+
+        init_bridge(self);
+
+        #[cfg(feature = "record")]
+        let recorded = init_recording::<D, R>(self, options.record_channel());
+
+        for update in bevy_duplex.rx().try_iter() {
+            if let SyncUpdate::Snapshot { value, .. } = update {
+                #[cfg(feature = "record")]
+                if recorded {
+                    record_initial_value::<D, R>(self, value.clone());
+                }
+
+                self.insert_resource(value);
+            }
+        }
+
+        add_sync_systems::<D, DiffedSync<R>, _, _, _>(
+            self,
+            &options,
+            apply_signal_diffed_resource::<D, R>,
+            send_signal_diffed_resource::<D, R>,
+            SyncSignalResourceSet,
+        );
+
+        #[cfg(feature = "record")]
+        if recorded {
+            add_replay_system(
+                self,
+                &options,
+                replay_leptos_resource::<D, R>,
+                SyncSignalResourceSet,
+            );
+        }
+
+        init_receiver_teardown::<D, SyncUpdate<R>>(self).insert_resource(bevy_duplex)
+    }
+
+    fn export_resource_to_leptos<S, R>(&mut self, bevy_tx: S) -> &mut Self
+    where
+        R: Resource + Clone,
+        S: HasSender<R> + Resource,
+    {
+        self.export_resource_to_leptos_with_options(bevy_tx, MessageOptions::default())
+    }
+
+    fn export_resource_to_leptos_with_options<S, R>(
+        &mut self,
+        bevy_tx: S,
+        options: MessageOptions,
+    ) -> &mut Self
+    where
+        R: Resource + Clone,
+        S: HasSender<R> + Resource,
+    {
+        init_bridge(self);
+
+        #[cfg(feature = "record")]
+        init_recording::<S, R>(self, options.record_channel());
+
+        init_sender_teardown::<S, R>(self)
+            .insert_resource(bevy_tx)
+            .add_systems(
+                options.export_schedule_or_default(),
+                export_resource::<S, R>
+                    .run_if(resource_exists::<S>)
+                    .in_set(ExportResourceSet),
+            )
+    }
+
     #[cfg(feature = "bevy_state")]
     fn sync_leptos_signal_with_state<D, S>(&mut self, bevy_duplex: D) -> &mut Self
     where
         S: bevy::state::state::FreelyMutableState + Clone,
         D: HasReceiver<S> + HasSender<S> + Resource,
     {
-        for message in bevy_duplex.rx().try_iter() {
-            self.insert_state(message);
+        self.sync_leptos_signal_with_state_with_options(bevy_duplex, SyncOptions::default())
+    }
+
+    #[cfg(feature = "bevy_state")]
+    fn sync_leptos_signal_with_state_with_options<D, S>(
+        &mut self,
+        bevy_duplex: D,
+        options: SyncOptions<S>,
+    ) -> &mut Self
+    where
+        S: bevy::state::state::FreelyMutableState + Clone,
+        D: HasReceiver<S> + HasSender<S> + Resource,
+    {
+        options.assert_supported(
+            "sync_leptos_signal_with_state",
+            &[
+                SyncFeature::Policy,
+                SyncFeature::Validator,
+                #[cfg(feature = "persist")]
+                SyncFeature::Persist,
+                SyncFeature::MaxSendRate,
+            ],
+        );
+
+        init_bridge(self);
+
+        #[cfg(feature = "record")]
+        let recorded = init_recording::<D, S>(self, options.record_channel());
+
+        for mut message in bevy_duplex.rx().try_iter() {
+            #[cfg(feature = "record")]
+            if recorded {
+                record_initial_value::<D, S>(self, message.clone());
+            }
+
+            let valid = self
+                .world()
+                .get_resource::<bevy::state::state::State<S>>()
+                .is_none_or(|current| {
+                    options.validate(current.get(), &mut message) != Validation::Invalid
+                });
+
+            if valid {
+                self.insert_state(message);
+            }
+        }
+
+        #[cfg(feature = "persist")]
+        if options.persistence().is_some() {
+            let current = self
+                .world()
+                .get_resource::<bevy::state::state::State<S>>()
+                .map(|state| state.get());
+            if let Some(value) = load_persisted(&options, &bevy_duplex, current) {
+                self.insert_state(value);
+            }
+
+            self.add_systems(Last, persist_state::<S>.in_set(PersistSet));
         }
 
-        self.insert_resource(bevy_duplex)
-            .add_systems(Update, sync_signal_state::<D, S>.in_set(SyncSignalStateSet))
+        let schedule = options.schedule_or(PreUpdate);
+        let sync_system = options.apply_run_conditions(
+            sync_signal_state::<D, S>
+                .run_if(resource_exists::<D>)
+                .in_set(SyncSignalStateSet),
+        );
+
+        #[cfg(feature = "record")]
+        if recorded {
+            self.add_systems(
+                schedule,
+                replay_leptos_state::<D, S>
+                    .in_set(ReplayLeptosSet)
+                    .before(SyncSignalStateSet),
+            );
+        }
+
+        init_receiver_teardown::<D, S>(self);
+        init_frame_end_tick(self)
+            .insert_resource(options)
+            .insert_resource(bevy_duplex)
+            .add_systems(schedule, sync_system)
+    }
+
+    #[cfg(feature = "bevy_state")]
+    fn export_state_to_leptos<T, S>(&mut self, bevy_tx: T) -> &mut Self
+    where
+        S: bevy::state::state::States,
+        T: HasSender<Option<S>> + Resource,
+    {
+        self.export_state_to_leptos_with_options(bevy_tx, MessageOptions::default())
+    }
+
+    #[cfg(feature = "bevy_state")]
+    fn export_state_to_leptos_with_options<T, S>(
+        &mut self,
+        bevy_tx: T,
+        options: MessageOptions,
+    ) -> &mut Self
+    where
+        S: bevy::state::state::States,
+        T: HasSender<Option<S>> + Resource,
+    {
+        init_bridge(self);
+
+        #[cfg(feature = "record")]
+        init_recording::<T, Option<S>>(self, options.record_channel());
+
+        init_sender_teardown::<T, Option<S>>(self)
+            .insert_resource(bevy_tx)
+            .add_systems(
+                options.export_schedule_or_default(),
+                export_state::<T, S>
+                    .run_if(resource_exists::<T>)
+                    .in_set(ExportStateSet),
+            )
+    }
+
+    #[cfg(feature = "bevy_state")]
+    fn export_state_transitions_to_leptos<T, S>(&mut self, bevy_tx: T) -> &mut Self
+    where
+        S: bevy::state::state::States,
+        T: HasSender<bevy::state::state::StateTransitionEvent<S>> + Resource,
+    {
+        self.export_message_to_leptos(bevy_tx)
     }
 
     fn sync_leptos_signal_with_query<D, F>(&mut self, duplex: BevyQueryDuplex<D, F>) -> &mut Self
@@ -124,7 +911,244 @@ impl LeptosBevyApp for App {
         for<'a> D: QueryDataOwned<'a> + Send + Sync + 'static,
         F: QueryFilter + 'static,
     {
-        self.insert_resource(duplex.duplex)
-            .add_systems(Update, sync_query::<D, F>.in_set(SyncQuerySet))
+        self.sync_leptos_signal_with_query_with_options(duplex, SyncOptions::default())
     }
+
+    fn sync_leptos_signal_with_query_with_options<D, F>(
+        &mut self,
+        duplex: BevyQueryDuplex<D, F>,
+        options: SyncOptions<Option<D>>,
+    ) -> &mut Self
+    where
+        for<'a> D: QueryDataOwned<'a> + Send + Sync + 'static,
+        F: QueryFilter + 'static,
+    {
+        options.assert_supported(
+            "sync_leptos_signal_with_query",
+            &[SyncFeature::MaxSendRate, SyncFeature::SendSchedule],
+        );
+
+        init_bridge(self);
+
+        add_sync_systems::<BevyMessageDuplex<Option<D>>, bool, _, _, _>(
+            self,
+            &options,
+            apply_signal_query::<D, F>,
+            send_signal_query::<D, F>,
+            SyncQuerySet,
+        );
+
+        #[cfg(feature = "record")]
+        if init_recording::<BevyMessageDuplex<Option<D>>, Option<D>>(self, options.record_channel())
+        {
+            add_replay_system(
+                self,
+                &options,
+                replay_leptos_query::<BevyMessageDuplex<Option<D>>, D, F>,
+                SyncQuerySet,
+            );
+        }
+
+        init_receiver_teardown::<BevyMessageDuplex<Option<D>>, Option<D>>(self);
+        self.insert_resource(options).insert_resource(duplex.duplex)
+    }
+
+    fn attach_bridges_from_leptos<R, D>(&mut self, bevy_rx: R) -> &mut Self
+    where
+        D: Resource,
+        R: HasReceiver<D> + Resource,
+    {
+        init_receiver_teardown::<R, D>(self);
+
+        self.insert_resource(bevy_rx).add_systems(
+            First,
+            attach_leptos_bridges::<R, D>
+                .run_if(resource_exists::<R>)
+                .after(RemoveClosedBridgesSet),
+        )
+    }
+}
+
+/// Adds the sync of a resource field. This is a function of its own to be able to name the types
+/// of the functions that project the resource to the field.
+fn add_resource_field_sync<D, R, F, G, GM>(
+    app: &mut App,
+    field_sync: ResourceFieldSync<D, G, GM>,
+    options: SyncOptions<F>,
+) -> &mut App
+where
+    R: Resource,
+    F: Clone + PartialEq + Send + Sync + 'static,
+    D: HasReceiver<F> + HasSender<F> + Send + Sync + 'static,
+    G: Fn(&R) -> &F + Send + Sync + 'static,
+    GM: Fn(&mut R) -> &mut F + Send + Sync + 'static,
+{
+    options.assert_supported(
+        "sync_leptos_signal_with_resource_field",
+        &[SyncFeature::SendSchedule],
+    );
+
+    init_bridge(app);
+
+    add_sync_systems::<ResourceFieldSync<D, G, GM>, Option<F>, _, _, _>(
+        app,
+        &options,
+        apply_signal_resource_field::<D, R, F, G, GM>,
+        send_signal_resource_field::<D, R, F, G, GM>,
+        SyncSignalResourceSet,
+    );
+
+    #[cfg(feature = "record")]
+    if init_recording::<ResourceFieldSync<D, G, GM>, F>(app, options.record_channel()) {
+        add_replay_system(
+            app,
+            &options,
+            replay_leptos_resource_field::<D, R, F, G, GM>,
+            SyncSignalResourceSet,
+        );
+    }
+
+    init_receiver_teardown::<ResourceFieldSync<D, G, GM>, F>(app).insert_resource(field_sync)
+}
+
+/// Adds the system that applies the values from Leptos in the sync schedule (`PreUpdate` by
+/// default) and the one that sends the changes of Bevy in the send schedule (`PostUpdate` by
+/// default) together with their [`SyncState`]. Both only run while the bridge resource `D`
+/// exists.
+fn add_sync_systems<D, S, T, M1, M2>(
+    app: &mut App,
+    options: &SyncOptions<T>,
+    apply: impl IntoScheduleConfigs<ScheduleSystem, M1>,
+    send: impl IntoScheduleConfigs<ScheduleSystem, M2>,
+    set: impl SystemSet + Clone,
+) where
+    D: Resource,
+    S: Default + Send + Sync + 'static,
+{
+    let tick = app.world().read_change_tick();
+
+    app.insert_resource(SyncState::<D, S>::new(tick))
+        .add_systems(
+            options.schedule_or(PreUpdate),
+            options.apply_run_conditions(
+                apply
+                    .run_if(resource_exists::<D>)
+                    .in_set(set.clone())
+                    .into_configs(),
+            ),
+        )
+        .add_systems(
+            options.send_schedule_or_default(),
+            options
+                .apply_run_conditions(send.run_if(resource_exists::<D>).in_set(set).into_configs()),
+        );
+}
+
+/// Initializes the `ImportedMessageIds` of the message `M` which are cleared at the start of
+/// every frame.
+fn init_imported_message_ids<M: Message>(app: &mut App) -> &mut App {
+    if !app.world().contains_resource::<ImportedMessageIds<M>>() {
+        app.init_resource::<ImportedMessageIds<M>>()
+            .add_systems(First, clear_imported_message_ids::<M>);
+    }
+
+    app
+}
+
+/// Initializes the `LeptosOutbox` which is flushed at the end of every frame.
+fn init_bridge(app: &mut App) -> &mut App {
+    if !app.world().contains_resource::<LeptosOutbox>() {
+        app.init_resource::<LeptosOutbox>()
+            .add_systems(Last, flush_leptos_outbox.in_set(FlushLeptosOutboxSet));
+    }
+
+    app
+}
+
+/// Removes the bridge resource `R` once Leptos has closed it.
+fn init_receiver_teardown<R, M>(app: &mut App) -> &mut App
+where
+    R: HasReceiver<M> + Resource,
+    M: Send + Sync + 'static,
+{
+    app.add_systems(
+        First,
+        remove_closed_receiver::<R, M>
+            .run_if(resource_exists::<R>)
+            .in_set(RemoveClosedBridgesSet),
+    )
+}
+
+/// Removes the bridge resource `S` once Leptos has closed it.
+fn init_sender_teardown<S, M>(app: &mut App) -> &mut App
+where
+    S: HasSender<M> + Resource,
+    M: Send + Sync + 'static,
+{
+    app.add_systems(
+        First,
+        remove_closed_sender::<S, M>
+            .run_if(resource_exists::<S>)
+            .in_set(RemoveClosedBridgesSet),
+    )
+}
+
+/// Adds the system that keeps track of the end of the frame for the `LastWriterWins` policy.
+fn init_frame_end_tick(app: &mut App) -> &mut App {
+    if !app.world().contains_resource::<LeptosFrameEndTick>() {
+        app.init_resource::<LeptosFrameEndTick>()
+            .add_systems(Last, update_frame_end_tick);
+    }
+
+    app
+}
+
+/// Records the bridge resource `B` that carries `T` under the name of `channel` and adds the
+/// system that counts the recorded frames. Returns `false` if the bridge isn't recorded.
+#[cfg(feature = "record")]
+fn init_recording<B, T>(app: &mut App, channel: Option<&RecordChannel>) -> bool
+where
+    B: 'static,
+    T: 'static,
+{
+    let Some(channel) = channel else {
+        return false;
+    };
+
+    channel.assert_value_type::<T>();
+
+    if !app.world().contains_resource::<BridgeCodecs>() {
+        app.init_resource::<BridgeCodecs>()
+            .add_systems(First, advance_bridge_frames);
+    }
+
+    app.world_mut()
+        .resource_mut::<BridgeCodecs>()
+        .register::<B>(channel.clone());
+
+    true
+}
+
+/// Adds the system that replays the recorded values of a sync right before the sync applies the
+/// values from Leptos.
+#[cfg(feature = "record")]
+fn add_replay_system<T, M>(
+    app: &mut App,
+    options: &SyncOptions<T>,
+    replay: impl IntoScheduleConfigs<ScheduleSystem, M>,
+    set: impl SystemSet,
+) {
+    app.add_systems(
+        options.schedule_or(PreUpdate),
+        replay.in_set(ReplayLeptosSet).before(set),
+    );
+}
+
+/// Remembers a value that has been sent from Leptos over the bridge resource `B` before the first
+/// frame so it is recorded once recording starts.
+#[cfg(feature = "record")]
+fn record_initial_value<B: 'static, T: Send + Sync + 'static>(app: &mut App, value: T) {
+    app.world_mut()
+        .get_resource_or_init::<InitialBridgeValues>()
+        .push::<B, T>(value);
 }
@@ -0,0 +1,104 @@
+use crate::messages::BevyMessageReceiver;
+use crate::plugin::{LeptosBevyCanvasCleanup, LeptosBevyCanvasPlugin};
+use crate::prelude::LeptosBevyApp;
+use bevy::prelude::*;
+use crossbeam_channel::Sender;
+use std::thread::JoinHandle;
+
+/// Runs a Bevy app on its own thread. This is the native counterpart of the
+/// [`BevyCanvas`](fn@crate::prelude::BevyCanvas) component for desktop builds where Leptos
+/// runs on the UI thread.
+///
+/// When this is dropped the Bevy app is asked to shut down via a `LeptosBevyCanvasCleanup`
+/// message and the thread is joined.
+///
+/// Please note that the app has to use a runner that works off the main thread like the one
+/// added by `MinimalPlugins` or `ScheduleRunnerPlugin`.
+///
+/// There are no animation frames natively so the Leptos side of the bridges has to be driven
+/// by calling [`run_frame`](crate::run_frame) regularly on the thread that owns the signals.
+/// Otherwise nothing that is written to a synced signal or sent as a message reaches Bevy.
+///
+/// ## Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use leptos_bevy_canvas::prelude::*;
+/// #
+/// let bevy_thread = BevyThread::spawn(|| {
+///     let mut app = App::new();
+///     app.add_plugins(MinimalPlugins);
+///     app
+/// });
+///
+/// // ... later, e.g. in `on_cleanup`
+/// let exit = bevy_thread.stop().expect("Bevy thread panicked");
+/// assert_eq!(exit, AppExit::Success);
+/// ```
+#[derive(Debug)]
+pub struct BevyThread {
+    shutdown_tx: Sender<LeptosBevyCanvasCleanup>,
+    handle: Option<JoinHandle<AppExit>>,
+}
+
+impl BevyThread {
+    /// Spawns a new thread, calls `init` on it to create the Bevy app and runs the app.
+    pub fn spawn(init: impl FnOnce() -> App + Send + 'static) -> Self {
+        let (shutdown_tx, shutdown_rx) = crossbeam_channel::bounded(1);
+
+        let handle = std::thread::Builder::new()
+            .name("bevy".to_string())
+            .spawn(move || {
+                let mut app = init();
+                app.add_plugins(LeptosBevyCanvasPlugin)
+                    .import_message_from_leptos(BevyMessageReceiver::new(shutdown_rx));
+                app.run()
+            })
+            .expect("couldn't spawn bevy thread");
+
+        Self {
+            shutdown_tx,
+            handle: Some(handle),
+        }
+    }
+
+    /// Returns `true` if the Bevy app has exited.
+    pub fn is_finished(&self) -> bool {
+        self.handle
+            .as_ref()
+            .is_none_or(|handle| handle.is_finished())
+    }
+
+    /// Asks the Bevy app to shut down without waiting for it.
+    pub fn request_shutdown(&self) {
+        // if the app already exited, the receiver is gone which is fine
+        let _ = self.shutdown_tx.try_send(LeptosBevyCanvasCleanup);
+    }
+
+    /// Waits for the Bevy app to exit by itself and returns its exit status.
+    ///
+    /// Returns an error if the Bevy thread panicked.
+    pub fn join(mut self) -> std::thread::Result<AppExit> {
+        self.handle
+            .take()
+            .expect("handle is only taken once")
+            .join()
+    }
+
+    /// Asks the Bevy app to shut down, waits for it and returns its exit status.
+    ///
+    /// Returns an error if the Bevy thread panicked.
+    pub fn stop(self) -> std::thread::Result<AppExit> {
+        self.request_shutdown();
+        self.join()
+    }
+}
+
+impl Drop for BevyThread {
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            self.request_shutdown();
+            let _ = handle.join();
+        }
+    }
+}
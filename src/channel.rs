@@ -0,0 +1,299 @@
+//! The channels that connect Leptos and Bevy.
+//!
+//! Messages are sent over bounded queues so that every single message arrives. Synced
+//! resources, states and queries are state-like though: only their latest value matters. They
+//! use a latest-value slot instead where a new value replaces the one that hasn't been read yet.
+//! This way the writer never blocks, no matter how long the reader doesn't read (e.g. while the
+//! browser tab is in the background), and the reader only ever gets the newest value.
+//!
+//! The slot is a `Mutex<Option<T>>`. The lock is only ever held to swap the value in or out
+//! (the replaced value is dropped after releasing it) and there is usually one writer and one
+//! reader per slot, so it is practically uncontended. In the browser everything runs on one
+//! thread anyway. A lock-free slot would need `unsafe` pointer juggling for no measurable gain.
+//!
+//! When the reactive owner that created the Leptos side of a bridge is disposed, Leptos closes
+//! its channels. Sending to a closed channel fails with a disconnected error and Bevy removes the
+//! resource of the bridge.
+
+use crate::utils::BridgeLock;
+use crossbeam_channel::{SendError, TryRecvError, TrySendError};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Creates a channel that queues up to `cap` values. Please see
+/// [`crossbeam_channel::bounded`] for details.
+pub fn bounded<T>(cap: usize) -> (Sender<T>, Receiver<T>) {
+    let (tx, rx) = crossbeam_channel::bounded(cap);
+    let closed = Arc::new(AtomicBool::new(false));
+
+    (
+        Sender {
+            kind: SenderKind::Queue(tx),
+            closed: Arc::clone(&closed),
+            bridge: BridgeLock::current(),
+        },
+        Receiver {
+            kind: ReceiverKind::Queue(rx),
+            closed,
+        },
+    )
+}
+
+/// Creates a channel that only keeps the latest value that has been sent.
+///
+/// Sending replaces the value that hasn't been received yet and never blocks. Receiving takes
+/// the value out of the slot.
+pub fn latest<T>() -> (Sender<T>, Receiver<T>) {
+    let slot = Arc::new(Slot {
+        value: Mutex::new(None),
+    });
+
+    let closed = Arc::new(AtomicBool::new(false));
+
+    (
+        Sender {
+            kind: SenderKind::Latest(Arc::clone(&slot)),
+            closed: Arc::clone(&closed),
+            bridge: BridgeLock::current(),
+        },
+        Receiver {
+            kind: ReceiverKind::Latest(slot),
+            closed,
+        },
+    )
+}
+
+/// Holds the latest value of a [`latest`] channel until it is received.
+struct Slot<T> {
+    value: Mutex<Option<T>>,
+}
+
+impl<T> Slot<T> {
+    fn replace(&self, value: T) {
+        let previous = self
+            .value
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .replace(value);
+
+        // dropped outside the lock
+        drop(previous);
+    }
+
+    fn take(&self) -> Option<T> {
+        self.value
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .take()
+    }
+}
+
+/// The sending side of a channel created by [`bounded`] or [`latest`].
+pub struct Sender<T> {
+    kind: SenderKind<T>,
+    closed: Arc<AtomicBool>,
+    /// The lock of the Leptos thread that created the channel
+    bridge: BridgeLock,
+}
+
+enum SenderKind<T> {
+    Queue(crossbeam_channel::Sender<T>),
+    Latest(Arc<Slot<T>>),
+}
+
+impl<T> Sender<T> {
+    /// Sends `value` without blocking. Fails if a queue is full or if the channel is closed or
+    /// disconnected. Sending to an open latest-value slot always succeeds.
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        if self.is_closed() {
+            return Err(TrySendError::Disconnected(value));
+        }
+
+        match &self.kind {
+            SenderKind::Queue(tx) => tx.try_send(value),
+            SenderKind::Latest(slot) => {
+                slot.replace(value);
+                Ok(())
+            }
+        }
+    }
+
+    /// Sends `value` and blocks while a queue is full. Sending to a latest-value slot never
+    /// blocks. Fails if the channel is closed or disconnected.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        if self.is_closed() {
+            return Err(SendError(value));
+        }
+
+        match &self.kind {
+            SenderKind::Queue(tx) => tx.send(value),
+            SenderKind::Latest(slot) => {
+                slot.replace(value);
+                Ok(())
+            }
+        }
+    }
+
+    /// Closes the channel for both sides.
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if either side has closed the channel.
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Relaxed)
+    }
+
+    /// How many more values fit into the channel right now. `None` for latest-value slots and
+    /// closed channels which never fill up.
+    pub(crate) fn remaining_capacity(&self) -> Option<usize> {
+        if self.is_closed() {
+            return None;
+        }
+
+        match &self.kind {
+            SenderKind::Queue(tx) => tx
+                .capacity()
+                .map(|capacity| capacity.saturating_sub(tx.len())),
+            SenderKind::Latest(_) => None,
+        }
+    }
+
+    /// How many values the channel can hold at most. `None` for latest-value slots.
+    pub(crate) fn capacity(&self) -> Option<usize> {
+        match &self.kind {
+            SenderKind::Queue(tx) => tx.capacity(),
+            SenderKind::Latest(_) => None,
+        }
+    }
+
+    /// Identifies the channel. All the senders and receivers of a channel share it.
+    pub(crate) fn id(&self) -> usize {
+        Arc::as_ptr(&self.closed) as usize
+    }
+
+    pub(crate) fn bridge_lock(&self) -> &BridgeLock {
+        &self.bridge
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            kind: match &self.kind {
+                SenderKind::Queue(tx) => SenderKind::Queue(tx.clone()),
+                SenderKind::Latest(slot) => SenderKind::Latest(Arc::clone(slot)),
+            },
+            closed: Arc::clone(&self.closed),
+            bridge: self.bridge.clone(),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Sender<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            SenderKind::Queue(tx) => tx.fmt(f),
+            SenderKind::Latest(_) => f.write_str("Sender { latest }"),
+        }
+    }
+}
+
+impl<T> From<crossbeam_channel::Sender<T>> for Sender<T> {
+    fn from(tx: crossbeam_channel::Sender<T>) -> Self {
+        Self {
+            kind: SenderKind::Queue(tx),
+            closed: Arc::default(),
+            bridge: BridgeLock::current(),
+        }
+    }
+}
+
+/// The receiving side of a channel created by [`bounded`] or [`latest`].
+pub struct Receiver<T> {
+    kind: ReceiverKind<T>,
+    closed: Arc<AtomicBool>,
+}
+
+enum ReceiverKind<T> {
+    Queue(crossbeam_channel::Receiver<T>),
+    Latest(Arc<Slot<T>>),
+}
+
+impl<T> Receiver<T> {
+    /// Receives a value without blocking.
+    pub fn try_recv(&self) -> Result<T, TryRecvError> {
+        match &self.kind {
+            ReceiverKind::Queue(rx) => rx.try_recv(),
+            ReceiverKind::Latest(slot) => slot.take().ok_or(TryRecvError::Empty),
+        }
+    }
+
+    /// Returns an iterator over all the values that have been sent but not received yet. For a
+    /// latest-value slot that is at most the newest value.
+    pub fn try_iter(&self) -> TryIter<'_, T> {
+        TryIter(match &self.kind {
+            ReceiverKind::Queue(rx) => TryIterKind::Queue(rx.try_iter()),
+            ReceiverKind::Latest(slot) => TryIterKind::Latest(slot.take().into_iter()),
+        })
+    }
+
+    /// Closes the channel for both sides.
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if either side has closed the channel.
+    pub fn is_closed(&self) -> bool {
+        self.closed.load(Ordering::Relaxed)
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        Self {
+            kind: match &self.kind {
+                ReceiverKind::Queue(rx) => ReceiverKind::Queue(rx.clone()),
+                ReceiverKind::Latest(slot) => ReceiverKind::Latest(Arc::clone(slot)),
+            },
+            closed: Arc::clone(&self.closed),
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Receiver<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.kind {
+            ReceiverKind::Queue(rx) => rx.fmt(f),
+            ReceiverKind::Latest(_) => f.write_str("Receiver { latest }"),
+        }
+    }
+}
+
+impl<T> From<crossbeam_channel::Receiver<T>> for Receiver<T> {
+    fn from(rx: crossbeam_channel::Receiver<T>) -> Self {
+        Self {
+            kind: ReceiverKind::Queue(rx),
+            closed: Arc::default(),
+        }
+    }
+}
+
+/// Iterator returned by [`Receiver::try_iter`].
+pub struct TryIter<'a, T>(TryIterKind<'a, T>);
+
+enum TryIterKind<'a, T> {
+    Queue(crossbeam_channel::TryIter<'a, T>),
+    Latest(std::option::IntoIter<T>),
+}
+
+impl<T> Iterator for TryIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.0 {
+            TryIterKind::Queue(iter) => iter.next(),
+            TryIterKind::Latest(iter) => iter.next(),
+        }
+    }
+}
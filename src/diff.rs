@@ -0,0 +1,234 @@
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash};
+
+pub use leptos_bevy_canvas_macros::Diffable;
+
+/// A value that can compute a patch from an older version of itself and apply such patches.
+///
+/// This is used by [`diffed_signal_synced`](crate::prelude::diffed_signal_synced) and
+/// [`LeptosBevyApp::sync_leptos_signal_with_diffed_resource`](crate::prelude::LeptosBevyApp::sync_leptos_signal_with_diffed_resource)
+/// to only send the parts of a large value that actually changed.
+///
+/// It is implemented for primitives and `String` (which are sent as a whole when they changed),
+/// as well as `Vec`s and `HashMap`s of diffable values. For your own structs you can derive it.
+///
+/// ```
+/// # use leptos_bevy_canvas::prelude::*;
+/// # use std::collections::HashMap;
+/// #
+/// #[derive(Diffable, Clone, Default)]
+/// struct Inventory {
+///     gold: u32,
+///     items: Vec<String>,
+///     stacks: HashMap<String, u32>,
+/// }
+///
+/// let old = Inventory::default();
+/// let mut new = old.clone();
+/// new.items.push("Sword".to_string());
+///
+/// let patch = new.diff(&old).unwrap();
+/// assert!(patch.gold.is_none());
+///
+/// let mut synced = old.clone();
+/// synced.apply_patch(patch).unwrap();
+/// assert_eq!(synced.items, vec!["Sword".to_string()]);
+/// ```
+pub trait Diffable: Clone + Send + Sync + 'static {
+    /// The type that describes the changes between two values.
+    type Patch: Clone + Send + Sync + 'static;
+
+    /// Returns the patch that turns `old` into `self` or `None` if nothing changed.
+    fn diff(&self, old: &Self) -> Option<Self::Patch>;
+
+    /// Applies a patch that has been created by [`Diffable::diff`]. Returns an error if the patch
+    /// doesn't fit this value because it has been created from a different one. In this case the
+    /// value might have been patched partially.
+    fn apply_patch(&mut self, patch: Self::Patch) -> Result<(), PatchError>;
+}
+
+/// Returned by [`Diffable::apply_patch`] if a patch doesn't fit the value it is applied to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PatchError;
+
+impl std::fmt::Display for PatchError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("patch doesn't fit the value it is applied to")
+    }
+}
+
+impl std::error::Error for PatchError {}
+
+/// What is sent over the channels of a diffed sync.
+///
+/// Both sides count the versions of the value they agreed on. Every patch names the version it
+/// has been created from. A patch is only applied if that is the version the receiver has synced
+/// last. Otherwise both sides changed the value at the same time and Bevy answers with a
+/// snapshot so they can't diverge.
+#[derive(Clone)]
+pub enum SyncUpdate<T: Diffable> {
+    /// The full value at `version`. Sent initially and whenever the sides have to be resynced.
+    Snapshot { version: u64, value: T },
+    /// The changes that turn the value at version `base` into the one at version `base + 1`.
+    Patch { base: u64, patch: T::Patch },
+    /// Asks Bevy to send a snapshot because a patch couldn't be applied.
+    Resync,
+}
+
+macro_rules! impl_diffable_whole {
+    ($($ty:ty),* $(,)?) => {
+        $(
+            impl Diffable for $ty {
+                type Patch = Self;
+
+                fn diff(&self, old: &Self) -> Option<Self::Patch> {
+                    (self != old).then(|| self.clone())
+                }
+
+                fn apply_patch(&mut self, patch: Self::Patch) -> Result<(), PatchError> {
+                    *self = patch;
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+impl_diffable_whole!(
+    bool, char, u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, String,
+);
+
+impl<T> Diffable for Option<T>
+where
+    T: Clone + PartialEq + Send + Sync + 'static,
+{
+    type Patch = Self;
+
+    fn diff(&self, old: &Self) -> Option<Self::Patch> {
+        (self != old).then(|| self.clone())
+    }
+
+    fn apply_patch(&mut self, patch: Self::Patch) -> Result<(), PatchError> {
+        *self = patch;
+        Ok(())
+    }
+}
+
+/// Patch for a `Vec`.
+#[derive(Clone)]
+pub struct VecPatch<T: Diffable> {
+    /// The length of the `Vec` the patch has been created from.
+    pub old_len: usize,
+    /// The length of the `Vec` after the patch. Shorter `Vec`s are truncated.
+    pub len: usize,
+    /// Patches for the items at the given indices that existed before.
+    pub changed: Vec<(usize, T::Patch)>,
+    /// Items that are appended after the old items.
+    pub appended: Vec<T>,
+}
+
+impl<T: Diffable> Diffable for Vec<T> {
+    type Patch = VecPatch<T>;
+
+    fn diff(&self, old: &Self) -> Option<Self::Patch> {
+        let changed = self
+            .iter()
+            .zip(old)
+            .enumerate()
+            .filter_map(|(index, (new, old))| new.diff(old).map(|patch| (index, patch)))
+            .collect::<Vec<_>>();
+
+        let appended = self.get(old.len()..).unwrap_or_default().to_vec();
+
+        (self.len() != old.len() || !changed.is_empty()).then(|| VecPatch {
+            old_len: old.len(),
+            len: self.len(),
+            changed,
+            appended,
+        })
+    }
+
+    fn apply_patch(&mut self, patch: Self::Patch) -> Result<(), PatchError> {
+        if self.len() != patch.old_len {
+            return Err(PatchError);
+        }
+
+        self.truncate(patch.len);
+
+        for (index, item_patch) in patch.changed {
+            self.get_mut(index)
+                .ok_or(PatchError)?
+                .apply_patch(item_patch)?;
+        }
+
+        self.extend(patch.appended);
+
+        Ok(())
+    }
+}
+
+/// Patch for a `HashMap`.
+#[derive(Clone)]
+pub struct HashMapPatch<K, V: Diffable> {
+    /// Keys that have been removed.
+    pub removed: Vec<K>,
+    /// Patches for the values of keys that existed before.
+    pub changed: Vec<(K, V::Patch)>,
+    /// Entries that have been inserted.
+    pub inserted: Vec<(K, V)>,
+}
+
+impl<K, V, S> Diffable for HashMap<K, V, S>
+where
+    K: Eq + Hash + Clone + Send + Sync + 'static,
+    V: Diffable,
+    S: BuildHasher + Clone + Send + Sync + 'static,
+{
+    type Patch = HashMapPatch<K, V>;
+
+    fn diff(&self, old: &Self) -> Option<Self::Patch> {
+        let removed = old
+            .keys()
+            .filter(|key| !self.contains_key(*key))
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let mut changed = vec![];
+        let mut inserted = vec![];
+
+        for (key, value) in self {
+            match old.get(key) {
+                Some(old_value) => {
+                    if let Some(patch) = value.diff(old_value) {
+                        changed.push((key.clone(), patch));
+                    }
+                }
+                None => inserted.push((key.clone(), value.clone())),
+            }
+        }
+
+        (!removed.is_empty() || !changed.is_empty() || !inserted.is_empty()).then_some(
+            HashMapPatch {
+                removed,
+                changed,
+                inserted,
+            },
+        )
+    }
+
+    fn apply_patch(&mut self, patch: Self::Patch) -> Result<(), PatchError> {
+        for key in patch.removed {
+            self.remove(&key).ok_or(PatchError)?;
+        }
+
+        for (key, value_patch) in patch.changed {
+            self.get_mut(&key)
+                .ok_or(PatchError)?
+                .apply_patch(value_patch)?;
+        }
+
+        self.extend(patch.inserted);
+
+        Ok(())
+    }
+}
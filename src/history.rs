@@ -0,0 +1,330 @@
+use crate::channel::{Receiver, Sender};
+use crate::systems::LeptosOutbox;
+use crate::transaction::send_to_bevy;
+use crate::utils::{close_on_cleanup, init_rw_signal_from_receiver};
+use bevy::ecs::change_detection::Tick;
+use bevy::ecs::system::SystemChangeTick;
+use bevy::prelude::*;
+use crossbeam_channel::TrySendError;
+use leptos::prelude::{Get, Signal, StoredValue, WithValue};
+use std::collections::VecDeque;
+
+/// A command that is sent from Leptos to the history of a synced resource.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HistoryCommand {
+    Undo,
+    Redo,
+    /// All the changes until the matching [`HistoryCommand::EndGroup`] are undone and redone
+    /// together. Groups can be nested.
+    BeginGroup,
+    EndGroup,
+}
+
+/// The state of a history that is sent from Bevy to Leptos.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct HistoryStatus {
+    pub can_undo: bool,
+    pub can_redo: bool,
+}
+
+/// Controls the undo/redo history of a synced resource from Leptos. Created by
+/// [`history_controls`].
+pub struct HistoryControls {
+    status: Signal<HistoryStatus>,
+    tx: StoredValue<Sender<HistoryCommand>>,
+}
+
+impl Clone for HistoryControls {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl Copy for HistoryControls {}
+
+impl HistoryControls {
+    /// `true` if there is a change that can be undone.
+    pub fn can_undo(&self) -> Signal<bool> {
+        let status = self.status;
+        Signal::derive(move || status.get().can_undo)
+    }
+
+    /// `true` if there is an undone change that can be redone.
+    pub fn can_redo(&self) -> Signal<bool> {
+        let status = self.status;
+        Signal::derive(move || status.get().can_redo)
+    }
+
+    /// Undoes the last change or group of changes.
+    pub fn undo(&self) {
+        self.send(HistoryCommand::Undo);
+    }
+
+    /// Redoes the last undone change or group of changes.
+    pub fn redo(&self) {
+        self.send(HistoryCommand::Redo);
+    }
+
+    /// Starts a group of changes that are undone together, e.g. when the user starts dragging
+    /// a slider.
+    pub fn begin_group(&self) {
+        self.send(HistoryCommand::BeginGroup);
+    }
+
+    /// Ends a group of changes started with [`HistoryControls::begin_group`].
+    pub fn end_group(&self) {
+        self.send(HistoryCommand::EndGroup);
+    }
+
+    fn send(&self, command: HistoryCommand) {
+        // Bevy doesn't read the commands right now so there is nothing to undo or redo anyway
+        if let Some(Err(TrySendError::Full(_))) =
+            self.tx.try_with_value(|tx| send_to_bevy(tx, command))
+        {
+            leptos::logging::warn!("Could not send history command because the channel is full");
+        }
+    }
+}
+
+/// This is passed to Bevy with [`SyncOptions::history`](crate::prelude::SyncOptions::history).
+#[derive(Clone)]
+pub struct BevyHistoryChannel {
+    rx: Receiver<HistoryCommand>,
+    tx: Sender<HistoryStatus>,
+}
+
+/// Creates the Leptos controls for the undo/redo history of a synced resource and the channel
+/// that has to be passed into the Bevy app.
+///
+/// ## Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use leptos_bevy_canvas::prelude::*;
+/// #
+/// #[derive(Resource, Clone)]
+/// struct EditorDocument {
+///     // ...
+/// }
+///
+/// # let (document, bevy_document) = signal_synced(EditorDocument {});
+/// let (history, bevy_history) = history_controls();
+///
+/// let mut app = App::new();
+/// app.sync_leptos_signal_with_resource_with_options(
+///     bevy_document,
+///     SyncOptions::default().history(bevy_history, 100),
+/// );
+///
+/// // in Leptos
+/// let can_undo = history.can_undo();
+/// history.undo();
+/// ```
+pub fn history_controls() -> (HistoryControls, BevyHistoryChannel) {
+    let (tx_l2b, rx_l2b) = crate::channel::bounded(50);
+    // only the latest status matters
+    let (tx_b2l, rx_b2l) = crate::channel::latest();
+
+    close_on_cleanup(&tx_l2b);
+    close_on_cleanup(&tx_b2l);
+
+    let status = init_rw_signal_from_receiver(&rx_b2l);
+
+    (
+        HistoryControls {
+            status: Signal::derive(move || status.get().unwrap_or_default()),
+            tx: StoredValue::new(tx_l2b),
+        },
+        BevyHistoryChannel {
+            rx: rx_l2b,
+            tx: tx_b2l,
+        },
+    )
+}
+
+/// The undo/redo history of the synced resource `R`.
+///
+/// Every frame in which the resource changes (from Bevy or from Leptos) becomes one entry. Use
+/// [`History::begin_group`] and [`History::end_group`] to combine the changes of several frames
+/// like a gizmo drag into one entry.
+#[derive(Resource)]
+pub struct History<R> {
+    undo: VecDeque<R>,
+    redo: Vec<R>,
+    /// The value after the last recorded change
+    current: Option<R>,
+    /// The value before the first change of the current group
+    group_start: Option<R>,
+    group_depth: u32,
+    depth: usize,
+    pending: Vec<HistoryCommand>,
+    /// The tick in which the history itself changed the resource
+    applied: Option<Tick>,
+    sent_status: Option<HistoryStatus>,
+    channel: Option<BevyHistoryChannel>,
+}
+
+impl<R> History<R> {
+    /// Creates a history that keeps at most `depth` entries.
+    pub fn new(depth: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            current: None,
+            group_start: None,
+            group_depth: 0,
+            depth,
+            pending: Vec::new(),
+            applied: None,
+            sent_status: None,
+            channel: None,
+        }
+    }
+
+    pub(crate) fn with_channel(mut self, channel: BevyHistoryChannel) -> Self {
+        self.channel = Some(channel);
+        self
+    }
+
+    /// Undoes the last change or group of changes at the end of this frame.
+    pub fn undo(&mut self) {
+        self.pending.push(HistoryCommand::Undo);
+    }
+
+    /// Redoes the last undone change or group of changes at the end of this frame.
+    pub fn redo(&mut self) {
+        self.pending.push(HistoryCommand::Redo);
+    }
+
+    /// Starts a group of changes that are undone together.
+    pub fn begin_group(&mut self) {
+        self.pending.push(HistoryCommand::BeginGroup);
+    }
+
+    /// Ends a group of changes started with [`History::begin_group`].
+    pub fn end_group(&mut self) {
+        self.pending.push(HistoryCommand::EndGroup);
+    }
+
+    /// The current status of the history.
+    pub fn status(&self) -> HistoryStatus {
+        HistoryStatus {
+            can_undo: !self.undo.is_empty() || self.group_start.is_some(),
+            can_redo: !self.redo.is_empty(),
+        }
+    }
+
+    /// Records a new change. Changes that have been undone can't be redone anymore.
+    fn push_undo(&mut self, value: R) {
+        self.undo.push_back(value);
+        self.trim_undo();
+
+        self.redo.clear();
+    }
+
+    /// Drops the oldest entries that exceed the depth of the history.
+    fn trim_undo(&mut self) {
+        while self.undo.len() > self.depth {
+            self.undo.pop_front();
+        }
+    }
+
+    fn record(&mut self, value: R) {
+        let previous = self.current.replace(value);
+
+        if self.group_depth > 0 {
+            if self.group_start.is_none() {
+                self.group_start = previous;
+            }
+        } else if let Some(previous) = previous {
+            self.push_undo(previous);
+        }
+    }
+
+    fn close_group(&mut self) {
+        self.group_depth = self.group_depth.saturating_sub(1);
+
+        if self.group_depth == 0 {
+            if let Some(start) = self.group_start.take() {
+                self.push_undo(start);
+            }
+        }
+    }
+}
+
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub struct HistorySet;
+
+/// Records the changes of the resource `R` in its [`History`] and applies undo and redo.
+pub fn update_history<R>(
+    resource: Option<ResMut<R>>,
+    mut history: ResMut<History<R>>,
+    outbox: Res<LeptosOutbox>,
+    ticks: SystemChangeTick,
+) where
+    R: Resource + Clone,
+{
+    let Some(mut resource) = resource else {
+        return;
+    };
+
+    let history = &mut *history;
+
+    if history.current.is_none() {
+        history.current = Some(resource.clone());
+    } else if resource.is_changed() && history.applied != Some(resource.last_changed()) {
+        history.record(resource.clone());
+    }
+
+    // the controls have been disposed. The history itself is kept.
+    if history
+        .channel
+        .as_ref()
+        .is_some_and(|channel| channel.rx.is_closed())
+    {
+        history.channel = None;
+    }
+
+    let mut commands = std::mem::take(&mut history.pending);
+    if let Some(channel) = &history.channel {
+        commands.extend(channel.rx.try_iter());
+    }
+
+    for command in commands {
+        match command {
+            HistoryCommand::BeginGroup => history.group_depth += 1,
+            HistoryCommand::EndGroup => history.close_group(),
+            HistoryCommand::Undo => {
+                // an open group is closed first so it can be undone
+                history.group_depth = 1;
+                history.close_group();
+
+                if let Some(value) = history.undo.pop_back() {
+                    if let Some(current) = history.current.replace(value.clone()) {
+                        history.redo.push(current);
+                    }
+                    *resource = value;
+                    history.applied = Some(ticks.this_run());
+                }
+            }
+            HistoryCommand::Redo => {
+                if let Some(value) = history.redo.pop() {
+                    if let Some(current) = history.current.replace(value.clone()) {
+                        history.undo.push_back(current);
+                        history.trim_undo();
+                    }
+                    *resource = value;
+                    history.applied = Some(ticks.this_run());
+                }
+            }
+        }
+    }
+
+    let status = history.status();
+    if history.sent_status != Some(status) {
+        if let Some(channel) = &history.channel {
+            outbox.send(&channel.tx, status);
+        }
+        history.sent_status = Some(status);
+    }
+}
@@ -0,0 +1,231 @@
+//! Exposes message channels to plain JavaScript/TypeScript code via `wasm-bindgen`.
+//!
+//! Channels are registered by name on the Leptos side with [`expose_sender_to_js`] and
+//! [`expose_receiver_to_js`]. Messages are encoded as JSON with `serde`. In JavaScript you can
+//! then use the functions exported by your wasm module:
+//!
+//! ```js
+//! // send a message to Bevy. Throws if the name is unknown, the JSON is invalid or the channel
+//! // is full or closed.
+//! leptosBevySend("click", JSON.stringify({ x: 1.0, y: 2.0 }));
+//!
+//! // receive messages from Bevy. The callback is called with the message as a JSON string.
+//! const id = leptosBevySubscribe("score", (json) => console.log(JSON.parse(json)));
+//! leptosBevyUnsubscribe("score", id);
+//! ```
+//!
+//! The same registry can be used from Rust with [`send_json`], [`subscribe_json`] and
+//! [`unsubscribe_json`].
+
+use crate::prelude::{LeptosChannelMessageReceiver, LeptosChannelMessageSender};
+use crossbeam_channel::TrySendError;
+use js_sys::Function;
+use leptos::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+
+type JsSender = Box<dyn Fn(&str) -> Result<(), JsSendError>>;
+type JsSubscriber = Rc<dyn Fn(&str)>;
+
+/// Returned by [`send_json`]. `leptosBevySend` throws an error with the same message.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum JsSendError {
+    /// No sender has been registered under this name.
+    UnknownChannel(String),
+    /// The JSON couldn't be deserialized into the message type of the channel.
+    InvalidJson(String),
+    /// Bevy hasn't read the messages for a while and the channel is full. Sending can succeed
+    /// again once Bevy has caught up.
+    Full,
+    /// The channel has been closed, e.g. because the Bevy app has been dropped.
+    Closed,
+}
+
+impl std::fmt::Display for JsSendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            JsSendError::UnknownChannel(name) => write!(f, "No sender registered as \"{name}\""),
+            JsSendError::InvalidJson(err) => write!(f, "Invalid message: {err}"),
+            JsSendError::Full => f.write_str("Channel is full"),
+            JsSendError::Closed => f.write_str("Channel is closed"),
+        }
+    }
+}
+
+impl std::error::Error for JsSendError {}
+
+#[derive(Default)]
+struct JsChannel {
+    /// The sender together with the id of its registration
+    sender: Option<(u32, JsSender)>,
+    subscribers: Vec<(u32, JsSubscriber)>,
+}
+
+#[derive(Default)]
+struct JsChannels {
+    next_subscriber_id: u32,
+    next_sender_id: u32,
+    channels: HashMap<String, JsChannel>,
+}
+
+thread_local! {
+    static JS_CHANNELS: RefCell<JsChannels> = RefCell::default();
+}
+
+/// Makes `sender` available to JavaScript under `name`. Any JSON sent with
+/// `leptosBevySend(name, json)` is deserialized and sent to Bevy where it ends up as a normal
+/// Bevy message if the corresponding receiver has been passed to `import_message_from_leptos`.
+///
+/// The sender is removed from the registry when the current reactive owner is cleaned up unless
+/// another sender has been registered under the same name in the meantime.
+pub fn expose_sender_to_js<S>(name: impl Into<String>, sender: S)
+where
+    S: LeptosChannelMessageSender + 'static,
+    S::Message: DeserializeOwned,
+{
+    let name = name.into();
+
+    let id = JS_CHANNELS.with_borrow_mut(|registry| {
+        let id = registry.next_sender_id;
+        registry.next_sender_id += 1;
+
+        let sender: JsSender = Box::new(move |json| {
+            let message = serde_json::from_str(json)
+                .map_err(|err| JsSendError::InvalidJson(err.to_string()))?;
+
+            sender.try_send(message).map_err(|err| match err {
+                TrySendError::Full(_) => JsSendError::Full,
+                TrySendError::Disconnected(_) => JsSendError::Closed,
+            })
+        });
+        registry.channels.entry(name.clone()).or_default().sender = Some((id, sender));
+
+        id
+    });
+
+    on_cleanup(move || {
+        JS_CHANNELS.with_borrow_mut(|registry| {
+            if let Some(channel) = registry.channels.get_mut(&name) {
+                if channel
+                    .sender
+                    .as_ref()
+                    .is_some_and(|(other_id, _)| *other_id == id)
+                {
+                    channel.sender = None;
+                }
+            }
+        });
+    });
+}
+
+/// Makes the messages received by `receiver` available to JavaScript under `name`. Every
+/// message is serialized to JSON and passed to all callbacks registered with
+/// `leptosBevySubscribe(name, callback)`. If Bevy sends several messages in one frame the
+/// callbacks are called once for each of them.
+///
+/// `receiver` is usually the `LeptosMessageReceiver` or `LeptosMessageDuplex` of a message that
+/// is exported from Bevy with `export_message_to_leptos`.
+pub fn expose_receiver_to_js<R>(name: impl Into<String>, receiver: R)
+where
+    R: LeptosChannelMessageReceiver,
+    R::Message: Serialize,
+{
+    let name = name.into();
+
+    receiver.on_message(move |message| {
+        let json = serde_json::to_string(message).expect("Could not serialize message");
+        notify_subscribers(&name, &json);
+    });
+}
+
+fn notify_subscribers(name: &str, json: &str) {
+    let subscribers = JS_CHANNELS.with_borrow(|registry| {
+        registry
+            .channels
+            .get(name)
+            .map(|channel| {
+                channel
+                    .subscribers
+                    .iter()
+                    .map(|(_, callback)| Rc::clone(callback))
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    });
+
+    for callback in subscribers {
+        callback(json);
+    }
+}
+
+/// Sends a JSON encoded message to the sender registered under `name`. This is what
+/// `leptosBevySend` calls.
+pub fn send_json(name: &str, json: &str) -> Result<(), JsSendError> {
+    JS_CHANNELS.with_borrow(|registry| {
+        let sender = registry
+            .channels
+            .get(name)
+            .and_then(|channel| channel.sender.as_ref())
+            .map(|(_, sender)| sender)
+            .ok_or_else(|| JsSendError::UnknownChannel(name.to_string()))?;
+
+        sender(json)
+    })
+}
+
+/// Calls `callback` with every JSON encoded message received on the channel registered under
+/// `name`. Returns an id that can be passed to [`unsubscribe_json`]. This is what
+/// `leptosBevySubscribe` calls.
+pub fn subscribe_json(name: &str, callback: impl Fn(&str) + 'static) -> u32 {
+    JS_CHANNELS.with_borrow_mut(|registry| {
+        let id = registry.next_subscriber_id;
+        registry.next_subscriber_id += 1;
+
+        registry
+            .channels
+            .entry(name.to_string())
+            .or_default()
+            .subscribers
+            .push((id, Rc::new(callback)));
+
+        id
+    })
+}
+
+/// Removes a callback that has been registered with [`subscribe_json`].
+pub fn unsubscribe_json(name: &str, id: u32) {
+    JS_CHANNELS.with_borrow_mut(|registry| {
+        if let Some(channel) = registry.channels.get_mut(name) {
+            channel.subscribers.retain(|(other_id, _)| *other_id != id);
+        }
+    });
+}
+
+/// Sends a JSON encoded message to the channel registered under `name`.
+#[wasm_bindgen(js_name = leptosBevySend)]
+pub fn js_send(name: &str, json: &str) -> Result<(), JsError> {
+    send_json(name, json).map_err(|err| JsError::new(&err.to_string()))
+}
+
+/// Calls `callback` with every JSON encoded message received on the channel registered under
+/// `name`. Returns an id that can be passed to `leptosBevyUnsubscribe`.
+#[wasm_bindgen(js_name = leptosBevySubscribe)]
+pub fn js_subscribe(name: &str, callback: Function) -> u32 {
+    let channel = name.to_string();
+
+    subscribe_json(name, move |json| {
+        if let Err(err) = callback.call1(&JsValue::NULL, &JsValue::from_str(json)) {
+            leptos::logging::error!("JS subscriber of \"{channel}\" threw: {err:?}");
+        }
+    })
+}
+
+/// Removes a callback that has been registered with `leptosBevySubscribe`.
+#[wasm_bindgen(js_name = leptosBevyUnsubscribe)]
+pub fn js_unsubscribe(name: &str, id: u32) {
+    unsubscribe_json(name, id);
+}
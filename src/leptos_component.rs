@@ -1,14 +1,18 @@
 use bevy::prelude::*;
 use leptos::prelude::*;
 
+#[cfg(not(feature = "ssr"))]
 use crate::{
-    messages::{message_l2b, LeptosChannelMessageSender},
+    messages::BevyMessageReceiver,
     plugin::{LeptosBevyCanvasCleanup, LeptosBevyCanvasPlugin},
     prelude::LeptosBevyApp,
 };
 
 /// Embeds a Bevy app in a Leptos component. It will add an HTML canvas element and start
 /// running the Bevy app inside it.
+///
+/// With the `ssr` feature enabled only the empty canvas is rendered on the server. The Bevy
+/// app is started on the client after hydration.
 #[component]
 pub fn BevyCanvas(
     /// This function is be called to initialize and return the Bevy app.
@@ -17,20 +21,27 @@ pub fn BevyCanvas(
     #[prop(into, default = "bevy_canvas".to_string())]
     canvas_id: String,
 ) -> impl IntoView {
-    let (shutdown_canvas, set_shutdown_canvas) = message_l2b::<LeptosBevyCanvasCleanup>();
+    #[cfg(not(feature = "ssr"))]
+    {
+        // unlike `message_l2b` this channel isn't closed on cleanup because the cleanup itself
+        // is sent over it
+        let (shutdown_canvas, set_shutdown_canvas) = crate::channel::bounded(1);
 
-    request_animation_frame(move || {
-        let mut app = init();
-        app.add_plugins(LeptosBevyCanvasPlugin)
-            .import_message_from_leptos(set_shutdown_canvas);
-        app.run();
-    });
+        request_animation_frame(move || {
+            let mut app = init();
+            app.add_plugins(LeptosBevyCanvasPlugin)
+                .import_message_from_leptos(BevyMessageReceiver::new(set_shutdown_canvas));
+            app.run();
+        });
 
-    on_cleanup(move || {
-        shutdown_canvas
-            .send(LeptosBevyCanvasCleanup)
-            .expect("couldn't send cleanup to bevy app");
-    });
+        on_cleanup(move || {
+            // fails only if the Bevy app has already exited
+            let _ = shutdown_canvas.try_send(LeptosBevyCanvasCleanup);
+        });
+    }
+
+    #[cfg(feature = "ssr")]
+    let _ = init;
 
     view! { <canvas id=canvas_id></canvas> }
 }
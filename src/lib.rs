@@ -102,19 +102,52 @@
 //! | 0.1, 0.2      | 0.7                       | 0.15                    |
 
 mod app_extension;
+#[cfg(not(target_arch = "wasm32"))]
+mod bevy_thread;
+pub mod channel;
+mod diff;
+mod history;
+#[cfg(feature = "js")]
+pub mod js;
 mod leptos_component;
 mod messages;
+mod observers;
+#[cfg(feature = "persist")]
+pub mod persist;
 mod plugin;
 mod queries;
+#[cfg(feature = "record")]
+pub mod record;
+mod resources;
+mod shared;
 mod signal_synced;
+#[cfg(feature = "bevy_state")]
+mod states;
+mod sync_options;
 pub mod systems;
+#[cfg(all(not(target_arch = "wasm32"), feature = "testing"))]
+pub mod testing;
 pub mod traits;
+mod transaction;
 mod utils;
 
+#[cfg(not(target_arch = "wasm32"))]
+pub use utils::run_frame;
+
 pub mod prelude {
     pub use crate::app_extension::*;
+    #[cfg(not(target_arch = "wasm32"))]
+    pub use crate::bevy_thread::*;
+    pub use crate::diff::*;
+    pub use crate::history::*;
     pub use crate::leptos_component::*;
     pub use crate::messages::*;
     pub use crate::queries::*;
+    pub use crate::resources::*;
+    pub use crate::shared::*;
     pub use crate::signal_synced::*;
+    #[cfg(feature = "bevy_state")]
+    pub use crate::states::*;
+    pub use crate::sync_options::*;
+    pub use crate::transaction::*;
 }
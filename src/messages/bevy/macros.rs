@@ -1,7 +1,7 @@
 macro_rules! impl_has_receiver {
     ($name:ident) => {
         impl<T> HasReceiver<T> for $name<T> {
-            fn rx(&self) -> &crossbeam_channel::Receiver<T> {
+            fn rx(&self) -> &crate::channel::Receiver<T> {
                 &self.rx
             }
         }
@@ -11,7 +11,7 @@ macro_rules! impl_has_receiver {
 macro_rules! impl_has_sender {
     ($name:ident) => {
         impl<T> HasSender<T> for $name<T> {
-            fn tx(&self) -> &crossbeam_channel::Sender<T> {
+            fn tx(&self) -> &crate::channel::Sender<T> {
                 &self.tx
             }
         }
@@ -1,8 +1,9 @@
 mod macros;
 
+use crate::channel::{Receiver, Sender};
+use crate::observers::Observers;
 use crate::traits::{HasReceiver, HasSender};
 use bevy::prelude::*;
-use crossbeam_channel::{Receiver, Sender};
 
 use crate::messages::bevy::macros::{impl_has_receiver, impl_has_sender};
 
@@ -30,8 +31,8 @@ impl<M> std::fmt::Debug for BevyMessageReceiver<M> {
 
 impl<M> BevyMessageReceiver<M> {
     #[inline]
-    pub fn new(rx: Receiver<M>) -> Self {
-        Self { rx }
+    pub fn new(rx: impl Into<Receiver<M>>) -> Self {
+        Self { rx: rx.into() }
     }
 }
 
@@ -61,8 +62,8 @@ impl<M> std::fmt::Debug for BevyMessageSender<M> {
 
 impl<M> BevyMessageSender<M> {
     #[inline]
-    pub fn new(tx: Sender<M>) -> Self {
-        Self { tx }
+    pub fn new(tx: impl Into<Sender<M>>) -> Self {
+        Self { tx: tx.into() }
     }
 }
 
@@ -73,6 +74,8 @@ impl_has_sender!(BevyMessageSender);
 pub struct BevyMessageDuplex<M> {
     tx: Sender<M>,
     rx: Receiver<M>,
+    observers: Option<Observers>,
+    lazy: bool,
 }
 
 impl<M> Clone for BevyMessageDuplex<M> {
@@ -80,6 +83,8 @@ impl<M> Clone for BevyMessageDuplex<M> {
         Self {
             tx: self.tx.clone(),
             rx: self.rx.clone(),
+            observers: self.observers.clone(),
+            lazy: self.lazy,
         }
     }
 }
@@ -89,16 +94,106 @@ impl<M> std::fmt::Debug for BevyMessageDuplex<M> {
         let mut s = f.debug_struct("BevyMessageDuplex");
         s.field("tx", &self.tx);
         s.field("rx", &self.rx);
+        s.field("lazy", &self.lazy);
         s.finish()
     }
 }
 
 impl<M> BevyMessageDuplex<M> {
     #[inline]
-    pub fn new(rx: Receiver<M>, tx: Sender<M>) -> Self {
-        Self { tx, rx }
+    pub fn new(rx: impl Into<Receiver<M>>, tx: impl Into<Sender<M>>) -> Self {
+        Self {
+            tx: tx.into(),
+            rx: rx.into(),
+            observers: None,
+            lazy: false,
+        }
+    }
+
+    pub(crate) fn with_observers(mut self, observers: Observers) -> Self {
+        self.observers = Some(observers);
+        self
+    }
+
+    /// Only sends values from Bevy to Leptos while the synced signal is observed, i.e. read by
+    /// an effect, a memo or a view that is alive. While nothing observes the signal, changes in
+    /// Bevy are neither cloned nor sent. As soon as the signal is observed again the current
+    /// value is sent on the next frame.
+    ///
+    /// Reading the signal untracked (e.g. in an event handler) doesn't count as observing it, so
+    /// such reads can return an outdated value. Values written in Leptos are always sent to Bevy.
+    /// If Bevy changed the value in the meantime, a write is based on an outdated value and would
+    /// overwrite these changes. Bevy drops such a write and sends its current value instead so the
+    /// signal is in sync again before the next write.
+    ///
+    /// Custom duplex types can opt into the same behavior by overriding
+    /// [`HasSender::is_observed`](crate::traits::HasSender::is_observed).
+    ///
+    /// This only has an effect for signals created by this crate like
+    /// [`signal_synced`](crate::prelude::signal_synced) or
+    /// [`single_query_signal`](crate::prelude::single_query_signal). Signals adopted with
+    /// [`sync_signal`](crate::prelude::sync_signal) are always considered observed.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use leptos_bevy_canvas::prelude::*;
+    /// #
+    /// #[derive(Resource, Clone, Default)]
+    /// struct Statistics {
+    ///     // ...
+    /// }
+    ///
+    /// # let (statistics, bevy_statistics) = signal_synced(Statistics::default());
+    /// let mut app = App::new();
+    /// app.sync_leptos_signal_with_resource(bevy_statistics.lazy());
+    /// ```
+    pub fn lazy(mut self) -> Self {
+        self.lazy = true;
+        self
+    }
+}
+
+impl<M> HasSender<M> for BevyMessageDuplex<M> {
+    fn tx(&self) -> &Sender<M> {
+        &self.tx
+    }
+
+    fn is_observed(&self) -> bool {
+        match (&self.observers, self.lazy) {
+            (Some(observers), true) => observers.is_observed(),
+            _ => true,
+        }
     }
 }
 
 impl_has_receiver!(BevyMessageDuplex);
-impl_has_sender!(BevyMessageDuplex);
+
+/// A message that is moved to Leptos by
+/// [`LeptosBevyApp::export_owned_message_to_leptos`](crate::prelude::LeptosBevyApp::export_owned_message_to_leptos)
+/// instead of being cloned.
+///
+/// The export drains all the `ToLeptos<M>` messages. Because it is a type of its own, readers of
+/// `M` in your app never miss a message. Only the export should read `ToLeptos<M>`.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use leptos_bevy_canvas::prelude::*;
+/// #
+/// // doesn't implement `Clone`
+/// struct Frame {
+///     pixels: Vec<u8>,
+/// }
+///
+/// fn render(mut frames: MessageWriter<ToLeptos<Frame>>) {
+///     frames.write(ToLeptos(Frame { pixels: vec![0; 1024] }));
+/// }
+///
+/// # let (frames, bevy_frames) = message_b2l::<Frame>();
+/// let mut app = App::new();
+/// app.export_owned_message_to_leptos(bevy_frames)
+///     .add_systems(Update, render);
+/// ```
+#[derive(Debug, Deref, DerefMut)]
+pub struct ToLeptos<M>(pub M);
+
+impl<M: Send + Sync + 'static> Message for ToLeptos<M> {}
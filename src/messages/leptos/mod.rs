@@ -1,8 +1,9 @@
 mod macros;
 mod traits;
 
+use crate::channel::{Receiver, Sender};
 use crate::messages::leptos::macros::impl_read_signal;
-use crossbeam_channel::{Receiver, Sender};
+use crate::utils::MessageListeners;
 use leptos::prelude::guards::{Plain, ReadGuard};
 use leptos::prelude::*;
 use std::panic::Location;
@@ -43,9 +44,9 @@ impl<M> LeptosMessageSender<M>
 where
     M: Send + Sync + 'static,
 {
-    pub fn new(tx: Sender<M>) -> Self {
+    pub fn new(tx: impl Into<Sender<M>>) -> Self {
         Self {
-            tx: StoredValue::new(tx),
+            tx: StoredValue::new(tx.into()),
         }
     }
 }
@@ -59,6 +60,7 @@ where
 {
     rx: StoredValue<Receiver<M>>,
     rx_signal: RwSignal<Option<M>>,
+    listeners: StoredValue<MessageListeners<M>>,
 }
 
 impl<M> Clone for LeptosMessageReceiver<M>
@@ -69,21 +71,44 @@ where
         Self {
             rx: self.rx,
             rx_signal: self.rx_signal,
+            listeners: self.listeners,
         }
     }
 }
 
 impl_read_signal!(LeptosMessageReceiver);
 
+impl<M> LeptosChannelMessageReceiver for LeptosMessageReceiver<M>
+where
+    M: Send + Sync + 'static,
+{
+    type Message = M;
+
+    fn on_message(&self, callback: impl FnMut(&M) + Send + Sync + 'static) {
+        self.listeners
+            .with_value(|listeners| listeners.add(callback));
+    }
+}
+
 impl<M> LeptosMessageReceiver<M>
 where
     M: Send + Sync + 'static,
 {
     #[inline]
-    pub fn new(rx: Receiver<M>, signal: RwSignal<Option<M>>) -> Self {
+    pub fn new(rx: impl Into<Receiver<M>>, signal: RwSignal<Option<M>>) -> Self {
+        Self::with_listeners(rx, signal, MessageListeners::default())
+    }
+
+    /// `listeners` have to be called with every message that is received by `signal`.
+    pub(crate) fn with_listeners(
+        rx: impl Into<Receiver<M>>,
+        signal: RwSignal<Option<M>>,
+        listeners: MessageListeners<M>,
+    ) -> Self {
         Self {
-            rx: StoredValue::new(rx),
+            rx: StoredValue::new(rx.into()),
             rx_signal: signal,
+            listeners: StoredValue::new(listeners),
         }
     }
 }
@@ -97,6 +122,7 @@ where
     tx: StoredValue<Sender<M>>,
     rx: StoredValue<Receiver<M>>,
     rx_signal: RwSignal<Option<M>>,
+    listeners: StoredValue<MessageListeners<M>>,
 }
 
 impl<M> Clone for LeptosMessageDuplex<M>
@@ -108,6 +134,7 @@ where
             tx: self.tx,
             rx: self.rx,
             rx_signal: self.rx_signal,
+            listeners: self.listeners,
         }
     }
 }
@@ -124,6 +151,18 @@ where
     }
 }
 
+impl<M> LeptosChannelMessageReceiver for LeptosMessageDuplex<M>
+where
+    M: Send + Sync + 'static,
+{
+    type Message = M;
+
+    fn on_message(&self, callback: impl FnMut(&M) + Send + Sync + 'static) {
+        self.listeners
+            .with_value(|listeners| listeners.add(callback));
+    }
+}
+
 impl_read_signal!(LeptosMessageDuplex);
 
 impl<M> LeptosMessageDuplex<M>
@@ -131,11 +170,26 @@ where
     M: Send + Sync + 'static,
 {
     #[inline]
-    pub fn new(rx: Receiver<M>, rx_signal: RwSignal<Option<M>>, tx: Sender<M>) -> Self {
+    pub fn new(
+        rx: impl Into<Receiver<M>>,
+        rx_signal: RwSignal<Option<M>>,
+        tx: impl Into<Sender<M>>,
+    ) -> Self {
+        Self::with_listeners(rx, rx_signal, tx, MessageListeners::default())
+    }
+
+    /// `listeners` have to be called with every message that is received by `rx_signal`.
+    pub(crate) fn with_listeners(
+        rx: impl Into<Receiver<M>>,
+        rx_signal: RwSignal<Option<M>>,
+        tx: impl Into<Sender<M>>,
+        listeners: MessageListeners<M>,
+    ) -> Self {
         Self {
-            tx: StoredValue::new(tx),
-            rx: StoredValue::new(rx),
+            tx: StoredValue::new(tx.into()),
+            rx: StoredValue::new(rx.into()),
             rx_signal,
+            listeners: StoredValue::new(listeners),
         }
     }
 }
@@ -1,4 +1,5 @@
-use crossbeam_channel::{SendError, Sender};
+use crate::channel::Sender;
+use crossbeam_channel::{SendError, TrySendError};
 use leptos::prelude::*;
 
 /// This is a trait that is implemented by a Leptos message sender.
@@ -8,8 +9,48 @@ pub trait LeptosChannelMessageSender {
     fn tx(&self) -> StoredValue<Sender<Self::Message>>;
 
     /// Call this to send an message to the Bevy app.
+    ///
+    /// This never blocks. If Bevy hasn't read the messages for a while (e.g. because the import
+    /// system doesn't run while its run condition is `false`) and the channel is full, the
+    /// message is returned in the error. Use [`LeptosChannelMessageSender::try_send`] to tell
+    /// this apart from a closed channel.
+    ///
+    /// With the `ssr` feature enabled this does nothing because there is no Bevy app running
+    /// on the server.
     #[inline]
     fn send(&self, message: Self::Message) -> Result<(), SendError<Self::Message>> {
-        self.tx().with_value(|tx| tx.send(message))
+        self.try_send(message)
+            .map_err(|err| SendError(err.into_inner()))
     }
+
+    /// Same as [`LeptosChannelMessageSender::send`] but the error tells whether the channel is
+    /// full or closed.
+    #[inline]
+    fn try_send(&self, message: Self::Message) -> Result<(), TrySendError<Self::Message>> {
+        #[cfg(feature = "ssr")]
+        {
+            let _ = message;
+            Ok(())
+        }
+
+        #[cfg(not(feature = "ssr"))]
+        self.tx()
+            .with_value(|tx| crate::transaction::send_to_bevy(tx, message))
+    }
+}
+
+/// This is a trait that is implemented by a Leptos message receiver.
+pub trait LeptosChannelMessageReceiver {
+    type Message: Send + Sync + 'static;
+
+    /// Calls `callback` with every message that is received from Bevy until the current
+    /// reactive owner is cleaned up.
+    ///
+    /// Reading the receiver like a signal only gives you the latest message. If Bevy sends
+    /// several messages in one frame the signal is only updated once. Use this if every single
+    /// message matters.
+    ///
+    /// The callback is called by receivers created by `message_b2l` or `message_duplex`. It
+    /// must not add callbacks to the same receiver.
+    fn on_message(&self, callback: impl FnMut(&Self::Message) + Send + Sync + 'static);
 }
@@ -3,7 +3,7 @@ mod leptos;
 
 pub use crate::messages::bevy::*;
 pub use crate::messages::leptos::*;
-use crate::utils::init_rw_signal_from_receiver;
+use crate::utils::{close_on_cleanup, init_message_signal, MessageListeners};
 
 /// Creates a pair of a `LeptosMessageSender` and a `BevyMessageReceiver`.
 ///
@@ -14,7 +14,9 @@ pub fn message_l2b<M>() -> (LeptosMessageSender<M>, BevyMessageReceiver<M>)
 where
     M: Send + Sync + 'static,
 {
-    let (tx, rx) = crossbeam_channel::bounded(50);
+    let (tx, rx) = crate::channel::bounded(50);
+
+    close_on_cleanup(&tx);
 
     (LeptosMessageSender::new(tx), BevyMessageReceiver::new(rx))
 }
@@ -29,12 +31,15 @@ pub fn message_b2l<M>() -> (LeptosMessageReceiver<M>, BevyMessageSender<M>)
 where
     M: Send + Sync + 'static,
 {
-    let (tx, rx) = crossbeam_channel::bounded(50);
+    let (tx, rx) = crate::channel::bounded(50);
+
+    close_on_cleanup(&tx);
 
-    let signal = init_rw_signal_from_receiver(&rx);
+    let listeners = MessageListeners::default();
+    let signal = init_message_signal(&rx, listeners.clone());
 
     (
-        LeptosMessageReceiver::new(rx, signal),
+        LeptosMessageReceiver::with_listeners(rx, signal, listeners),
         BevyMessageSender::new(tx),
     )
 }
@@ -45,13 +50,17 @@ pub fn message_duplex<M>() -> (LeptosMessageDuplex<M>, BevyMessageDuplex<M>)
 where
     M: Send + Sync + 'static,
 {
-    let (tx_l2b, rx_l2b) = crossbeam_channel::bounded(50);
-    let (tx_b2l, rx_b2l) = crossbeam_channel::bounded(50);
+    let (tx_l2b, rx_l2b) = crate::channel::bounded(50);
+    let (tx_b2l, rx_b2l) = crate::channel::bounded(50);
+
+    close_on_cleanup(&tx_l2b);
+    close_on_cleanup(&tx_b2l);
 
-    let signal = init_rw_signal_from_receiver(&rx_b2l);
+    let listeners = MessageListeners::default();
+    let signal = init_message_signal(&rx_b2l, listeners.clone());
 
     (
-        LeptosMessageDuplex::new(rx_b2l, signal, tx_l2b),
+        LeptosMessageDuplex::with_listeners(rx_b2l, signal, tx_l2b, listeners),
         BevyMessageDuplex::new(rx_l2b, tx_b2l),
     )
 }
@@ -0,0 +1,39 @@
+use leptos::reactive::graph::{AnySubscriber, Observer};
+use std::sync::{Arc, Mutex};
+
+/// Keeps track of the reactive observers (effects, memos and views) that read a synced signal.
+///
+/// The observers are only held weakly so an observer counts as long as it is alive. When the
+/// component that reads the signal is unmounted its effects are dropped and the signal isn't
+/// observed anymore.
+#[derive(Clone, Default)]
+pub(crate) struct Observers {
+    subscribers: Arc<Mutex<Vec<AnySubscriber>>>,
+}
+
+impl Observers {
+    /// Registers the current observer if there is one. Called whenever the signal is tracked.
+    pub fn track(&self) {
+        let Some(observer) = Observer::get() else {
+            return;
+        };
+
+        let mut subscribers = self.subscribers.lock().expect("Lock poisoned");
+        subscribers.retain(|subscriber| subscriber.1.strong_count() > 0);
+
+        if !subscribers
+            .iter()
+            .any(|subscriber| subscriber.0 == observer.0)
+        {
+            subscribers.push(observer);
+        }
+    }
+
+    /// Returns `true` if there is at least one observer that is still alive.
+    pub fn is_observed(&self) -> bool {
+        let mut subscribers = self.subscribers.lock().expect("Lock poisoned");
+        subscribers.retain(|subscriber| subscriber.1.strong_count() > 0);
+
+        !subscribers.is_empty()
+    }
+}
@@ -0,0 +1,306 @@
+//! Persist synced resources and states so they survive reloads.
+//!
+//! Persistence is configured with [`SyncOptions::persist`]. The value is encoded as JSON with
+//! `serde` and stored under a versioned key. The persisted value is loaded when the sync is
+//! added to the Bevy app, i.e. before the first frame, and overrides the initial value of the
+//! Leptos signal. After that it is saved every time it changes.
+//!
+//! ## Example
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use leptos_bevy_canvas::prelude::*;
+//! # use leptos_bevy_canvas::persist::MemoryStorage;
+//! # use serde::{Deserialize, Serialize};
+//! #
+//! #[derive(Resource, Clone, Serialize, Deserialize)]
+//! struct Settings {
+//!     sensitivity: f32,
+//! }
+//!
+//! // in the browser you would use `LocalStorage` instead
+//! let storage = MemoryStorage::default();
+//!
+//! # let (settings, bevy_settings) = signal_synced(Settings { sensitivity: 1.0 });
+//! let mut app = App::new();
+//! app.sync_leptos_signal_with_resource_with_options(
+//!     bevy_settings,
+//!     SyncOptions::default()
+//!         .persist(storage.clone(), "settings")
+//!         .persist_version(1),
+//! );
+//! ```
+
+use crate::sync_options::{SyncOptions, Validation};
+use crate::traits::HasSender;
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// A key-value store that synced values can be persisted to.
+pub trait SyncStorage: Send + Sync + 'static {
+    /// Returns the value stored under `key` if there is one.
+    fn get(&self, key: &str) -> Option<String>;
+
+    /// Stores `value` under `key`.
+    fn set(&self, key: &str, value: &str);
+
+    /// Removes the value stored under `key`.
+    fn remove(&self, key: &str);
+}
+
+/// Stores values in the browser's `localStorage`. Outside the browser nothing is stored.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct LocalStorage;
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorage {
+    fn storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+}
+
+impl SyncStorage for LocalStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        #[cfg(target_arch = "wasm32")]
+        return Self::storage()?.get_item(key).ok()?;
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            let _ = key;
+            None
+        }
+    }
+
+    fn set(&self, key: &str, value: &str) {
+        #[cfg(target_arch = "wasm32")]
+        if let Some(storage) = Self::storage() {
+            if storage.set_item(key, value).is_err() {
+                leptos::logging::error!("Could not persist \"{key}\" to localStorage");
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let _ = (key, value);
+    }
+
+    fn remove(&self, key: &str) {
+        #[cfg(target_arch = "wasm32")]
+        if let Some(storage) = Self::storage() {
+            let _ = storage.remove_item(key);
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let _ = key;
+    }
+}
+
+/// Stores values in memory. Clones share the same values which is useful in tests.
+#[derive(Clone, Debug, Default)]
+pub struct MemoryStorage {
+    values: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl SyncStorage for MemoryStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        self.values.lock().expect("Lock poisoned").get(key).cloned()
+    }
+
+    fn set(&self, key: &str, value: &str) {
+        self.values
+            .lock()
+            .expect("Lock poisoned")
+            .insert(key.to_string(), value.to_string());
+    }
+
+    fn remove(&self, key: &str) {
+        self.values.lock().expect("Lock poisoned").remove(key);
+    }
+}
+
+/// Stores every value in a file `<key>.json` in a directory. Not available on wasm.
+///
+/// All characters of the key except ASCII letters, digits, `-`, `_` and `@` are percent-encoded
+/// in the file name so a key like `../settings` can't point outside of the directory.
+#[cfg(not(target_arch = "wasm32"))]
+#[derive(Clone, Debug)]
+pub struct FileStorage {
+    dir: std::path::PathBuf,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileStorage {
+    /// Creates a storage in `dir`. The directory is created when the first value is stored.
+    pub fn new(dir: impl Into<std::path::PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    fn path(&self, key: &str) -> std::path::PathBuf {
+        let mut name = String::with_capacity(key.len() + 5);
+
+        for byte in key.bytes() {
+            if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'@') {
+                name.push(byte as char);
+            } else {
+                name.push_str(&format!("%{byte:02X}"));
+            }
+        }
+
+        name.push_str(".json");
+
+        self.dir.join(name)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl SyncStorage for FileStorage {
+    fn get(&self, key: &str) -> Option<String> {
+        std::fs::read_to_string(self.path(key)).ok()
+    }
+
+    fn set(&self, key: &str, value: &str) {
+        let result =
+            std::fs::create_dir_all(&self.dir).and_then(|_| std::fs::write(self.path(key), value));
+
+        if let Err(err) = result {
+            leptos::logging::error!("Could not persist \"{key}\": {err}");
+        }
+    }
+
+    fn remove(&self, key: &str) {
+        let _ = std::fs::remove_file(self.path(key));
+    }
+}
+
+/// Where and how a synced value is persisted. Created by [`SyncOptions::persist`].
+pub struct Persistence<T> {
+    storage: Arc<dyn SyncStorage>,
+    key: String,
+    version: u32,
+    encode: fn(&T) -> Option<String>,
+    decode: fn(&str) -> Option<T>,
+}
+
+impl<T> Clone for Persistence<T> {
+    fn clone(&self) -> Self {
+        Self {
+            storage: Arc::clone(&self.storage),
+            key: self.key.clone(),
+            version: self.version,
+            encode: self.encode,
+            decode: self.decode,
+        }
+    }
+}
+
+impl<T> Persistence<T> {
+    pub(crate) fn new(storage: impl SyncStorage, key: String) -> Self
+    where
+        T: Serialize + DeserializeOwned,
+    {
+        Self {
+            storage: Arc::new(storage),
+            key,
+            version: 0,
+            encode: |value| serde_json::to_string(value).ok(),
+            decode: |json| serde_json::from_str(json).ok(),
+        }
+    }
+
+    pub(crate) fn set_version(&mut self, version: u32) {
+        self.version = version;
+    }
+
+    /// The key including the version. Values persisted with another version are ignored.
+    pub fn versioned_key(&self) -> String {
+        format!("{}@v{}", self.key, self.version)
+    }
+
+    /// Loads the persisted value if there is one.
+    pub fn load(&self) -> Option<T> {
+        let json = self.storage.get(&self.versioned_key())?;
+        let value = (self.decode)(&json);
+
+        if value.is_none() {
+            leptos::logging::warn!(
+                "Could not decode persisted value \"{}\"",
+                self.versioned_key()
+            );
+        }
+
+        value
+    }
+
+    /// Persists `value`.
+    pub fn save(&self, value: &T) {
+        match (self.encode)(value) {
+            Some(json) => self.storage.set(&self.versioned_key(), &json),
+            None => leptos::logging::warn!("Could not encode value \"{}\"", self.versioned_key()),
+        }
+    }
+}
+
+/// Loads the persisted value for a synced value that is added to the app and sends it to
+/// Leptos. The value is checked by the validator of the sync against the `current` value just
+/// like a value written from Leptos and dropped if it's invalid.
+pub(crate) fn load_persisted<T, D>(
+    options: &SyncOptions<T>,
+    sync: &D,
+    current: Option<&T>,
+) -> Option<T>
+where
+    T: Clone,
+    D: HasSender<T>,
+{
+    let persistence = options.persistence()?;
+    let mut value = persistence.load()?;
+
+    if current.is_some_and(|current| options.validate(current, &mut value) == Validation::Invalid) {
+        leptos::logging::warn!(
+            "Ignoring invalid persisted value \"{}\"",
+            persistence.versioned_key()
+        );
+        return None;
+    }
+
+    // if Leptos has already closed the channel the bridge is removed anyway
+    let _ = sync.tx().send(value.clone());
+
+    Some(value)
+}
+
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub struct PersistSet;
+
+/// Saves a synced resource whenever it changes.
+pub fn persist_resource<R>(resource: Option<Res<R>>, options: Res<SyncOptions<R>>)
+where
+    R: Resource,
+{
+    let (Some(resource), Some(persistence)) = (resource, options.persistence()) else {
+        return;
+    };
+
+    if resource.is_changed() {
+        persistence.save(&resource);
+    }
+}
+
+/// Saves a synced state whenever it changes.
+#[cfg(feature = "bevy_state")]
+pub fn persist_state<S>(
+    state: Option<Res<bevy::state::state::State<S>>>,
+    options: Res<SyncOptions<S>>,
+) where
+    S: bevy::state::state::States,
+{
+    let (Some(state), Some(persistence)) = (state, options.persistence()) else {
+        return;
+    };
+
+    if state.is_changed() {
+        persistence.save(state.get());
+    }
+}
@@ -1,5 +1,6 @@
 use crate::messages::BevyMessageDuplex;
 use crate::signal_synced::{signal_synced, RwSignalSynced};
+use bevy::ecs::change_detection::Tick;
 use bevy::ecs::component::Mutable;
 use bevy::ecs::query::{QueryData, QueryFilter};
 use bevy::prelude::*;
@@ -47,6 +48,12 @@ pub trait QueryDataOwned<'q> {
     fn set_query_data<'w, 's>(&self, data: &mut <Self::Qdata as QueryData>::Item<'w, 's>);
 
     fn is_changed<'w, 's>(data: &<Self::Qdata as QueryData>::Item<'w, 's>) -> bool;
+
+    /// The tick at which the newest of the components has been changed.
+    fn last_changed<'w, 's>(
+        data: &<Self::Qdata as QueryData>::Item<'w, 's>,
+        this_run: Tick,
+    ) -> Tick;
 }
 
 macro_rules! impl_as_query_data {
@@ -86,6 +93,25 @@ macro_rules! impl_as_query_data {
                     false
                 }
             }
+
+            fn last_changed<'w, 's>(
+                data: &<Self::Qdata as QueryData>::Item<'w, 's>,
+                this_run: Tick,
+            ) -> Tick {
+                paste! {
+                    let ($([<$name:lower>],)*) = data;
+                    [$([<$name:lower>].last_changed(),)*]
+                        .into_iter()
+                        .reduce(|newest, tick| {
+                            if tick.is_newer_than(newest, this_run) {
+                                tick
+                            } else {
+                                newest
+                            }
+                        })
+                        .unwrap_or(this_run)
+                }
+            }
         }
     };
 }
@@ -113,3 +139,16 @@ where
         }
     }
 }
+
+impl<D, F> BevyQueryDuplex<D, F>
+where
+    for<'a> D: QueryDataOwned<'a>,
+    F: QueryFilter,
+{
+    /// Only sends the query data to Leptos while the signal is observed. Please see
+    /// [`BevyMessageDuplex::lazy`] for details.
+    pub fn lazy(mut self) -> Self {
+        self.duplex = self.duplex.lazy();
+        self
+    }
+}
@@ -0,0 +1,499 @@
+//! Record the traffic that crosses the bridge between Leptos and Bevy and replay it later in a
+//! fresh Bevy app to reproduce bugs deterministically.
+//!
+//! Every bridge that should be recorded is given a channel name with
+//! [`SyncOptions::record`](crate::prelude::SyncOptions::record) or
+//! [`MessageOptions::record`](crate::prelude::MessageOptions::record) when it's added to the app.
+//! Recording starts as soon as a [`BridgeRecorder`] resource is inserted and replaying starts as
+//! soon as a [`BridgeReplay`] resource is inserted. Frame numbers are counted from the frame in
+//! which the respective resource was inserted.
+//!
+//! Every entry of the log names the channel it crossed so bridges that carry the same type are
+//! kept apart. To replay a log, add the same bridges with the same channel names to the fresh
+//! app. The recorded values are fed into the app in the schedule of the bridge. Values that
+//! Leptos sent before the first frame, like the initial value of a synced signal, are recorded in
+//! the first frame of the recording.
+//!
+//! ## Example
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use leptos_bevy_canvas::prelude::*;
+//! # use leptos_bevy_canvas::record::{BridgeRecorder, BridgeReplay};
+//! # use serde::{Deserialize, Serialize};
+//! #
+//! #[derive(Message, Clone, Serialize, Deserialize)]
+//! struct Click {
+//!     x: f32,
+//!     y: f32,
+//! }
+//!
+//! # let (click_sender, bevy_click_receiver) = message_l2b::<Click>();
+//! let mut app = App::new();
+//! app.import_message_from_leptos_with_options(
+//!     bevy_click_receiver,
+//!     MessageOptions::default().record::<Click>("clicks"),
+//! )
+//! .insert_resource(BridgeRecorder::default());
+//!
+//! click_sender.send(Click { x: 1.0, y: 2.0 }).unwrap();
+//! app.update();
+//!
+//! let log = app.world_mut().remove_resource::<BridgeRecorder>().unwrap().into_log();
+//! let json = serde_json::to_string(&log).unwrap();
+//!
+//! // ... later in a test
+//! # let (_click_sender, bevy_click_receiver) = message_l2b::<Click>();
+//! let mut app = App::new();
+//! app.import_message_from_leptos_with_options(
+//!     bevy_click_receiver,
+//!     MessageOptions::default().record::<Click>("clicks"),
+//! )
+//! .insert_resource(BridgeReplay::new(serde_json::from_str(&json).unwrap()));
+//! app.update();
+//!
+//! let clicks = app.world().resource::<Messages<Click>>();
+//! assert_eq!(clicks.len(), 1);
+//! ```
+
+use crate::prelude::QueryDataOwned;
+use crate::systems::ResourceFieldSync;
+use bevy::ecs::query::QueryFilter;
+use bevy::ecs::system::SystemParam;
+use bevy::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::any::{type_name, Any, TypeId};
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::ops::RangeInclusive;
+
+/// The direction in which a value crossed the bridge.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum BridgeDirection {
+    LeptosToBevy,
+    BevyToLeptos,
+}
+
+/// A single value that crossed the bridge.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BridgeLogEntry {
+    /// The frame in which the value crossed the bridge, counted from the frame in which
+    /// recording started.
+    pub frame: u64,
+    pub direction: BridgeDirection,
+    /// The name of the channel that has been given to `record`. Two bridges that carry the same
+    /// type are kept apart by it.
+    pub channel: String,
+    /// The type name of the message or synced value.
+    pub value_type: String,
+    pub value: serde_json::Value,
+}
+
+/// A serializable log of all the values that crossed the bridge. Entries are ordered by frame.
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct BridgeLog {
+    pub entries: Vec<BridgeLogEntry>,
+}
+
+impl BridgeLog {
+    /// Returns the values for the given channel that crossed the bridge in the given frame and
+    /// direction.
+    pub fn values<'a>(
+        &'a self,
+        frame: u64,
+        direction: BridgeDirection,
+        channel: &'a str,
+    ) -> impl Iterator<Item = &'a serde_json::Value> + 'a {
+        self.entries(frame..=frame, direction, channel)
+            .map(|entry| &entry.value)
+    }
+
+    fn entries<'a>(
+        &'a self,
+        frames: RangeInclusive<u64>,
+        direction: BridgeDirection,
+        channel: &'a str,
+    ) -> impl Iterator<Item = &'a BridgeLogEntry> + 'a {
+        let start = self
+            .entries
+            .partition_point(|entry| entry.frame < *frames.start());
+
+        self.entries[start..]
+            .iter()
+            .take_while(move |entry| entry.frame <= *frames.end())
+            .filter(move |entry| entry.direction == direction && entry.channel == channel)
+    }
+}
+
+/// Insert this resource to start recording. Take it out of the world again to get the
+/// [`BridgeLog`].
+#[derive(Resource, Debug, Default)]
+pub struct BridgeRecorder {
+    frame: u64,
+    log: BridgeLog,
+}
+
+impl BridgeRecorder {
+    /// The log recorded so far.
+    pub fn log(&self) -> &BridgeLog {
+        &self.log
+    }
+
+    /// Stops recording and returns the log.
+    pub fn into_log(self) -> BridgeLog {
+        self.log
+    }
+}
+
+/// Insert this resource to replay a recorded [`BridgeLog`]. All the values that were sent from
+/// Leptos to Bevy are fed into the app in the same frames they were recorded in.
+#[derive(Resource, Debug, Default)]
+pub struct BridgeReplay {
+    frame: u64,
+    log: BridgeLog,
+}
+
+impl BridgeReplay {
+    pub fn new(log: BridgeLog) -> Self {
+        Self { frame: 0, log }
+    }
+
+    /// The frame that is currently being replayed.
+    pub fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Returns `true` if all frames of the log have been replayed.
+    pub fn is_finished(&self) -> bool {
+        self.log
+            .entries
+            .last()
+            .is_none_or(|entry| entry.frame < self.frame)
+    }
+}
+
+/// The name under which a bridge is recorded and how its values are encoded. Created by
+/// [`SyncOptions::record`](crate::prelude::SyncOptions::record) and
+/// [`MessageOptions::record`](crate::prelude::MessageOptions::record).
+#[derive(Clone)]
+pub struct RecordChannel {
+    name: String,
+    value_type: TypeId,
+    value_type_name: &'static str,
+    encode: fn(&dyn Any) -> serde_json::Value,
+    decode: fn(serde_json::Value) -> Box<dyn Any>,
+}
+
+impl std::fmt::Debug for RecordChannel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RecordChannel")
+            .field("name", &self.name)
+            .field("value_type", &self.value_type_name)
+            .finish()
+    }
+}
+
+impl RecordChannel {
+    pub(crate) fn new<T>(name: String) -> Self
+    where
+        T: Serialize + DeserializeOwned + 'static,
+    {
+        Self {
+            name,
+            value_type: TypeId::of::<T>(),
+            value_type_name: type_name::<T>(),
+            encode: |value| {
+                let value = value
+                    .downcast_ref::<T>()
+                    .expect("registered with this type");
+                serde_json::to_value(value).expect("Could not serialize recorded value")
+            },
+            decode: |value| {
+                Box::new(
+                    serde_json::from_value::<T>(value).expect("Could not deserialize replay value"),
+                )
+            },
+        }
+    }
+
+    /// Panics if the channel has been created for another type than the bridge carries.
+    pub(crate) fn assert_value_type<T: 'static>(&self) {
+        assert!(
+            self.value_type == TypeId::of::<T>(),
+            "The record channel \"{}\" has been created for `{}` but the bridge carries `{}`",
+            self.name,
+            self.value_type_name,
+            type_name::<T>(),
+        );
+    }
+}
+
+/// The channels of the bridges that are recorded by the type of their bridge resource.
+#[derive(Resource, Default)]
+pub struct BridgeCodecs {
+    channels: HashMap<TypeId, RecordChannel>,
+}
+
+impl BridgeCodecs {
+    /// Records the bridge resource `B` under the name of `channel`.
+    pub(crate) fn register<B: 'static>(&mut self, channel: RecordChannel) {
+        let bridge = TypeId::of::<B>();
+
+        if self
+            .channels
+            .iter()
+            .any(|(other, other_channel)| *other != bridge && other_channel.name == channel.name)
+        {
+            panic!(
+                "The record channel \"{}\" is used by more than one bridge",
+                channel.name
+            );
+        }
+
+        self.channels.insert(bridge, channel);
+    }
+
+    /// Appends `value` to the log of `recorder` if the bridge is recorded.
+    fn record(
+        &self,
+        recorder: &mut BridgeRecorder,
+        direction: BridgeDirection,
+        bridge: TypeId,
+        value: &dyn Any,
+    ) {
+        let Some(channel) = self.channels.get(&bridge) else {
+            return;
+        };
+
+        let frame = recorder.frame;
+        recorder.log.entries.push(BridgeLogEntry {
+            frame,
+            direction,
+            channel: channel.name.clone(),
+            value_type: channel.value_type_name.to_string(),
+            value: (channel.encode)(value),
+        });
+    }
+}
+
+/// A value that has been sent from Leptos before the first frame, e.g. the initial value of a
+/// synced signal which is applied when the sync is added to the app.
+struct InitialBridgeValue {
+    bridge: TypeId,
+    value: Box<dyn Any + Send + Sync>,
+}
+
+/// The values that have been sent from Leptos before the first frame. They are recorded in the
+/// first frame of the recording.
+#[derive(Resource, Default)]
+pub struct InitialBridgeValues {
+    values: Vec<InitialBridgeValue>,
+}
+
+impl InitialBridgeValues {
+    /// Remembers `value` that has been received by the bridge resource `B`.
+    pub(crate) fn push<B: 'static, T: Send + Sync + 'static>(&mut self, value: T) {
+        self.values.push(InitialBridgeValue {
+            bridge: TypeId::of::<B>(),
+            value: Box::new(value),
+        });
+    }
+}
+
+/// Used by the bridge systems to record values that cross the bridge.
+#[derive(SystemParam)]
+pub struct BridgeRecording<'w> {
+    recorder: Option<ResMut<'w, BridgeRecorder>>,
+    codecs: Option<Res<'w, BridgeCodecs>>,
+}
+
+impl BridgeRecording<'_> {
+    /// Records `value` that crossed the bridge resource `B` if a [`BridgeRecorder`] is present
+    /// and the bridge is recorded.
+    pub fn record<B: 'static, T: 'static>(&mut self, direction: BridgeDirection, value: &T) {
+        if let (Some(recorder), Some(codecs)) = (&mut self.recorder, &self.codecs) {
+            codecs.record(recorder, direction, TypeId::of::<B>(), value);
+        }
+    }
+}
+
+/// Used by the replay systems to get the values that Leptos sent over the bridge resource `B`.
+#[derive(SystemParam)]
+pub struct LeptosReplay<'w, 's, B: 'static> {
+    replay: Option<Res<'w, BridgeReplay>>,
+    codecs: Option<Res<'w, BridgeCodecs>>,
+    /// The first frame that hasn't been replayed yet
+    next_frame: Local<'s, u64>,
+    marker: PhantomData<fn() -> B>,
+}
+
+impl<B: 'static> LeptosReplay<'_, '_, B> {
+    /// The values that have been sent from Leptos in the frames since the last call in the order
+    /// they were recorded. Usually that's the current frame but a system that doesn't run in
+    /// every frame, like one in `FixedPreUpdate`, gets the values of the skipped frames as well.
+    pub fn values<T: 'static>(&mut self) -> Vec<T> {
+        let (Some(replay), Some(codecs)) = (&self.replay, &self.codecs) else {
+            return Vec::new();
+        };
+
+        let Some(channel) = codecs.channels.get(&TypeId::of::<B>()) else {
+            return Vec::new();
+        };
+
+        // a new replay starts with its first frame
+        if replay.is_added() {
+            *self.next_frame = 0;
+        }
+
+        let frames = *self.next_frame..=replay.frame;
+        *self.next_frame = replay.frame + 1;
+
+        replay
+            .log
+            .entries(frames, BridgeDirection::LeptosToBevy, &channel.name)
+            .map(|entry| {
+                *(channel.decode)(entry.value.clone())
+                    .downcast::<T>()
+                    .expect("registered with this type")
+            })
+            .collect()
+    }
+}
+
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub struct ReplayLeptosSet;
+
+/// Advances the frame counters of the [`BridgeRecorder`] and the [`BridgeReplay`]. When
+/// recording starts the [`InitialBridgeValues`] are recorded in its first frame.
+pub fn advance_bridge_frames(
+    recorder: Option<ResMut<BridgeRecorder>>,
+    replay: Option<ResMut<BridgeReplay>>,
+    codecs: Res<BridgeCodecs>,
+    initial: Option<ResMut<InitialBridgeValues>>,
+) {
+    if let Some(mut recorder) = recorder {
+        if !recorder.is_added() {
+            recorder.frame += 1;
+        } else if let Some(mut initial) = initial {
+            for value in std::mem::take(&mut initial.values) {
+                codecs.record(
+                    &mut recorder,
+                    BridgeDirection::LeptosToBevy,
+                    value.bridge,
+                    &*value.value,
+                );
+            }
+        }
+    }
+
+    if let Some(mut replay) = replay {
+        if !replay.is_added() {
+            replay.frame += 1;
+        }
+    }
+}
+
+/// Writes the recorded messages from Leptos as Bevy messages.
+pub fn replay_leptos_messages<B, M>(
+    mut replay: LeptosReplay<B>,
+    mut message_writer: MessageWriter<M>,
+) where
+    B: 'static,
+    M: Message,
+{
+    message_writer.write_batch(replay.values::<M>());
+}
+
+/// Sets the resource to the recorded values from Leptos.
+pub fn replay_leptos_resource<B, R>(
+    mut replay: LeptosReplay<B>,
+    resource: Option<ResMut<R>>,
+    mut commands: Commands,
+) where
+    B: 'static,
+    R: Resource,
+{
+    let Some(value) = replay.values::<R>().pop() else {
+        return;
+    };
+
+    match resource {
+        Some(mut resource) => *resource = value,
+        None => commands.insert_resource(value),
+    }
+}
+
+/// Inserts or removes the resource according to the recorded values from Leptos.
+pub fn replay_leptos_optional_resource<B, R>(
+    mut replay: LeptosReplay<B>,
+    resource: Option<ResMut<R>>,
+    mut commands: Commands,
+) where
+    B: 'static,
+    R: Resource,
+{
+    let Some(value) = replay.values::<Option<R>>().pop() else {
+        return;
+    };
+
+    match (value, resource) {
+        (Some(value), Some(mut resource)) => *resource = value,
+        (Some(value), None) => commands.insert_resource(value),
+        (None, Some(_)) => commands.remove_resource::<R>(),
+        (None, None) => {}
+    }
+}
+
+/// Sets the field of the resource to the recorded values from Leptos.
+pub fn replay_leptos_resource_field<D, R, F, G, GM>(
+    mut replay: LeptosReplay<ResourceFieldSync<D, G, GM>>,
+    field_sync: Res<ResourceFieldSync<D, G, GM>>,
+    resource: Option<ResMut<R>>,
+) where
+    R: Resource,
+    F: Send + Sync + 'static,
+    D: Send + Sync + 'static,
+    G: Send + Sync + 'static,
+    GM: Fn(&mut R) -> &mut F + Send + Sync + 'static,
+{
+    let (Some(value), Some(mut resource)) = (replay.values::<F>().pop(), resource) else {
+        return;
+    };
+
+    *field_sync.field_mut(&mut *resource) = value;
+}
+
+/// Sets the next state to the recorded values from Leptos.
+#[cfg(feature = "bevy_state")]
+pub fn replay_leptos_state<B, S>(
+    mut replay: LeptosReplay<B>,
+    next_state: Option<ResMut<bevy::state::state::NextState<S>>>,
+) where
+    B: 'static,
+    S: bevy::state::state::FreelyMutableState,
+{
+    if let (Some(value), Some(mut next_state)) = (replay.values::<S>().pop(), next_state) {
+        next_state.set(value);
+    }
+}
+
+/// Sets the query data to the recorded values from Leptos.
+pub fn replay_leptos_query<B, D, F>(
+    mut replay: LeptosReplay<B>,
+    mut query: Query<<D as QueryDataOwned>::Qdata, F>,
+) where
+    B: 'static,
+    for<'a> D: QueryDataOwned<'a> + Send + Sync + 'static,
+    F: QueryFilter,
+{
+    let values = replay.values::<Option<D>>();
+
+    let Ok(mut item) = query.single_mut() else {
+        return;
+    };
+
+    for value in values.into_iter().flatten() {
+        value.set_query_data(&mut item);
+    }
+}
@@ -0,0 +1,40 @@
+use crate::messages::BevyMessageSender;
+use crate::utils::{close_on_cleanup, init_rw_signal_from_receiver};
+use leptos::prelude::*;
+
+/// Read-only synchronization for Bevy resources.
+///
+/// Creates a pair of a read-only `Signal` and a `BevyMessageSender` for a Bevy resource that is
+/// only ever changed by Bevy. Pass the sender to
+/// [`LeptosBevyApp::export_resource_to_leptos`](crate::prelude::LeptosBevyApp::export_resource_to_leptos).
+///
+/// The signal is `None` until Bevy has sent the resource for the first time. After that it is
+/// updated every time the resource changes.
+///
+/// ## Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use leptos_bevy_canvas::prelude::*;
+/// #
+/// #[derive(Resource, Clone)]
+/// struct Score(u32);
+///
+/// let (score, bevy_score_sender) = resource_signal::<Score>();
+///
+/// let mut app = App::new();
+/// app.insert_resource(Score(0))
+///     .export_resource_to_leptos(bevy_score_sender);
+/// ```
+pub fn resource_signal<R>() -> (Signal<Option<R>>, BevyMessageSender<R>)
+where
+    R: Send + Sync + 'static,
+{
+    let (tx, rx) = crate::channel::latest();
+
+    close_on_cleanup(&tx);
+
+    let signal = init_rw_signal_from_receiver(&rx);
+
+    (signal.read_only().into(), BevyMessageSender::new(tx))
+}
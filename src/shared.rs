@@ -0,0 +1,111 @@
+use bevy::ecs::component::{Component, Mutable, StorageType};
+use bevy::prelude::*;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+/// A value that is shared between Leptos and Bevy instead of being cloned.
+///
+/// Cloning a `Shared<T>` only clones the `Arc` so sending it over the bridge is cheap no matter
+/// how large `T` is. Both sides read the same allocation. Mutating it (through `DerefMut` or
+/// [`Shared::make_mut`]) is copy-on-write: the value is only cloned if the other side still
+/// holds on to it.
+///
+/// `Shared<T>` is a `Resource`, a `Component` and a `Message` so it can be used with all the
+/// syncs and exports of this crate.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use leptos::prelude::*;
+/// # use leptos_bevy_canvas::prelude::*;
+/// #
+/// #[derive(Clone, Default)]
+/// struct PointCloud {
+///     points: Vec<Vec3>,
+/// }
+///
+/// fn add_point(mut cloud: ResMut<Shared<PointCloud>>) {
+///     // only clones the points if Leptos still holds the previous value
+///     cloud.points.push(Vec3::ZERO);
+/// }
+///
+/// # let owner = Owner::new();
+/// # owner.with(|| {
+/// let (cloud, bevy_cloud) = signal_synced(Shared::new(PointCloud::default()));
+///
+/// let mut app = App::new();
+/// app.sync_leptos_signal_with_resource(bevy_cloud)
+///     .add_systems(Update, add_point);
+/// # });
+/// ```
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Shared<T>(Arc<T>);
+
+impl<T> Shared<T> {
+    /// Moves the value into a new shared allocation.
+    pub fn new(value: T) -> Self {
+        Self(Arc::new(value))
+    }
+
+    /// Returns `true` if both point to the same allocation.
+    pub fn ptr_eq(this: &Self, other: &Self) -> bool {
+        Arc::ptr_eq(&this.0, &other.0)
+    }
+
+    /// Returns the inner `Arc`.
+    pub fn into_arc(this: Self) -> Arc<T> {
+        this.0
+    }
+}
+
+impl<T: Clone> Shared<T> {
+    /// Returns a mutable reference to the value. It is cloned first if it is shared.
+    pub fn make_mut(this: &mut Self) -> &mut T {
+        Arc::make_mut(&mut this.0)
+    }
+
+    /// Returns the value. It is cloned if it is shared.
+    pub fn unwrap_or_clone(this: Self) -> T {
+        Arc::unwrap_or_clone(this.0)
+    }
+}
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> Deref for Shared<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<T: Clone> DerefMut for Shared<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        Shared::make_mut(self)
+    }
+}
+
+impl<T> From<T> for Shared<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl<T> From<Arc<T>> for Shared<T> {
+    fn from(value: Arc<T>) -> Self {
+        Self(value)
+    }
+}
+
+impl<T: Send + Sync + 'static> Resource for Shared<T> {}
+
+impl<T: Send + Sync + 'static> Message for Shared<T> {}
+
+impl<T: Send + Sync + 'static> Component for Shared<T> {
+    const STORAGE_TYPE: StorageType = StorageType::Table;
+    type Mutability = Mutable;
+}
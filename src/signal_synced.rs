@@ -1,15 +1,30 @@
+use crate::channel::Sender;
+use crate::diff::{Diffable, SyncUpdate};
 use crate::messages::BevyMessageDuplex;
-use crossbeam_channel::Sender;
+use crate::observers::Observers;
+use crate::transaction::{in_transaction, on_next_frame_or_commit, send_to_bevy};
+use crate::utils::{close_on_cleanup, on_next_frame, use_frame_fn};
+use crossbeam_channel::TrySendError;
 use leptos::prelude::guards::{Plain, ReadGuard};
 use leptos::prelude::*;
-use std::ops::DerefMut;
+use leptos::reactive::effect::ImmediateEffect;
+use leptos_use::core::UseRwSignal;
+use std::ops::{Deref, DerefMut};
 use std::panic::Location;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+/// Sends the current value of the signal to Bevy.
+type SendToBevy = Arc<dyn Fn() + Send + Sync>;
 
 /// This is basically identical to a Leptos `RwSignal` but is automatically synced with a Bevy
 /// type like a `Resource` or a `Query`.
 pub struct RwSignalSynced<T> {
     rw_signal: RwSignal<T>,
-    tx: StoredValue<Sender<T>>,
+    send: StoredValue<SendToBevy>,
+    /// `true` if the signal has been written to and the value will be sent on the next frame.
+    dirty: StoredValue<bool>,
+    observers: StoredValue<Observers>,
 }
 
 impl<T> Clone for RwSignalSynced<T> {
@@ -20,6 +35,56 @@ impl<T> Clone for RwSignalSynced<T> {
 
 impl<T> Copy for RwSignalSynced<T> {}
 
+impl<T> RwSignalSynced<T>
+where
+    T: Send + Sync + Clone + 'static,
+{
+    /// Returns a writable slice of a part of the synced value, e.g. a field of a struct. This
+    /// is the same as Leptos' `create_slice` and can be used with `bind:value` directly.
+    ///
+    /// The returned signal only notifies when the value returned by `getter` changes. Writing to
+    /// the setter writes through this signal and thus syncs the whole value with Bevy.
+    ///
+    /// ```
+    /// # use leptos::prelude::*;
+    /// # use leptos_bevy_canvas::prelude::*;
+    /// #
+    /// #[derive(Clone)]
+    /// struct Settings {
+    ///     name: String,
+    ///     volume: f32,
+    /// }
+    ///
+    /// # let owner = Owner::new();
+    /// # owner.with(|| {
+    /// let (settings, bevy_settings) = signal_synced(Settings {
+    ///     name: "Player".to_string(),
+    ///     volume: 0.5,
+    /// });
+    ///
+    /// let name = settings.slice(
+    ///     |settings| settings.name.clone(),
+    ///     |settings, name| settings.name = name,
+    /// );
+    ///
+    /// view! { <input bind:value=name /> };
+    /// # });
+    /// ```
+    pub fn slice<O>(
+        self,
+        getter: impl Fn(&T) -> O + Send + Sync + 'static,
+        setter: impl Fn(&mut T, O) + Send + Sync + 'static,
+    ) -> (Signal<O>, SignalSetter<O>)
+    where
+        O: PartialEq + Send + Sync + 'static,
+    {
+        let memo = Memo::new(move |_| self.with(&getter));
+        let setter = SignalSetter::map(move |value| self.update(|inner| setter(inner, value)));
+
+        (memo.into(), setter)
+    }
+}
+
 impl<T> DefinedAt for RwSignalSynced<T> {
     fn defined_at(&self) -> Option<&'static Location<'static>> {
         self.rw_signal.defined_at()
@@ -53,6 +118,7 @@ where
 {
     fn track(&self) {
         self.rw_signal.track();
+        self.observers.try_with_value(Observers::track);
     }
 }
 
@@ -75,17 +141,29 @@ where
     fn try_write(&self) -> Option<impl UntrackableGuard<Target = Self::Value>> {
         let inner_guard = self.rw_signal.try_write()?;
 
-        request_animation_frame({
-            let rw_signal = self.rw_signal;
-            let tx = self.tx;
+        // all the writes of a frame are sent together once at the next frame or when the
+        // transaction is committed
+        if !self.dirty.get_value() || in_transaction() {
+            self.dirty.set_value(true);
 
-            move || {
-                tx.with_value(|tx| {
-                    tx.send(rw_signal.get_untracked())
-                        .expect("Could not send value")
-                });
-            }
-        });
+            let key = self
+                .send
+                .with_value(|send| Arc::as_ptr(send).cast::<()>() as usize);
+
+            on_next_frame_or_commit(key, {
+                let send = self.send;
+                let dirty = self.dirty;
+
+                move || {
+                    // the value has already been sent when a transaction has been committed
+                    if dirty.try_update_value(std::mem::take) != Some(true) {
+                        return;
+                    }
+
+                    send.try_with_value(|send| send());
+                }
+            });
+        }
 
         Some(inner_guard)
     }
@@ -97,49 +175,312 @@ where
     }
 }
 
-// TODO : make sync_resource out of this with an `Into<UseRwSignal>` as input?
-
 /// Creates a pair of a `RwSignalSynced` and a `BevyMessageDuplex`.
 ///
 /// The first can be used just like a `RwSignal` in Leptos. The `BevyMessageDuplex` that has to
 /// be passed into the Bevy app where it will be used to sync the signal with a Bevy `Resource` or
 /// a `Query`.
+///
+/// Only the latest value is kept in each direction. If one side doesn't read for a while (e.g.
+/// because the browser tab is in the background) the other side simply replaces the unread value
+/// and never blocks.
 pub fn signal_synced<T>(initial_value: T) -> (RwSignalSynced<T>, BevyMessageDuplex<T>)
 where
     T: Send + Sync + Clone + 'static,
 {
-    let (tx_l2b, rx_l2b) = crossbeam_channel::bounded(50);
-    let (tx_b2l, rx_b2l) = crossbeam_channel::bounded(50);
+    let (tx_l2b, rx_l2b) = crate::channel::latest();
+    let (tx_b2l, rx_b2l) = crate::channel::latest();
+
+    close_on_cleanup(&tx_l2b);
+    close_on_cleanup(&tx_b2l);
 
     tx_l2b
         .send(initial_value.clone())
-        .expect("Could not send initial value");
+        .expect("new channels aren't closed");
 
     let signal = RwSignal::new(initial_value);
+    let observers = Observers::default();
 
-    #[cfg(target_arch = "wasm32")]
-    {
-        leptos_use::use_raf_fn({
-            let rx = rx_b2l.clone();
+    use_frame_fn(move || {
+        for message in rx_b2l.try_iter() {
+            signal.set(message);
+        }
+    });
 
-            move |_| {
-                for message in rx.try_iter() {
-                    signal.set(message);
+    (
+        RwSignalSynced {
+            rw_signal: signal,
+            send: StoredValue::new(Arc::new(move || {
+                if let Some(value) = signal.try_get_untracked() {
+                    // a latest-value slot only fails once it has been closed and then the value
+                    // isn't needed anymore
+                    let _ = send_to_bevy(&tx_l2b, value);
                 }
+            })),
+            dirty: StoredValue::new(false),
+            observers: StoredValue::new(observers.clone()),
+        },
+        BevyMessageDuplex::new(rx_l2b, tx_b2l).with_observers(observers),
+    )
+}
+
+/// Syncs an already existing signal with Bevy. Returns the `BevyMessageDuplex` that has to be
+/// passed into the Bevy app just like the one returned by [`signal_synced`].
+///
+/// Use this if the signal comes from somewhere else like a component prop or `use_local_storage`.
+/// Accepts anything that can be converted into a `UseRwSignal` like an `RwSignal` or a tuple of a
+/// read and a write signal. All the writes to the signal in a frame are sent to Bevy once on
+/// the next frame.
+///
+/// ```
+/// # use leptos::prelude::*;
+/// # use leptos_bevy_canvas::prelude::*;
+/// #
+/// # let owner = Owner::new();
+/// # owner.with(|| {
+/// let (volume, set_volume) = signal(0.5);
+///
+/// let bevy_volume = sync_signal((volume, set_volume));
+/// // pass `bevy_volume` to `sync_leptos_signal_with_resource` in your Bevy app
+/// # });
+/// ```
+pub fn sync_signal<R, W, T>(signal: impl Into<UseRwSignal<R, W, T>>) -> BevyMessageDuplex<T>
+where
+    R: Read + ReadUntracked + Track + Copy + Send + Sync + 'static,
+    <R as Read>::Value: Deref<Target = T>,
+    <R as ReadUntracked>::Value: Deref<Target = T>,
+    W: Write<Value = T> + Copy + Send + Sync + 'static,
+    T: Send + Sync + Clone + 'static,
+{
+    let signal = signal.into();
+
+    let (tx_l2b, rx_l2b) = crate::channel::latest();
+    let (tx_b2l, rx_b2l) = crate::channel::latest();
+
+    close_on_cleanup(&tx_l2b);
+    close_on_cleanup(&tx_b2l);
+
+    tx_l2b
+        .send(signal.get_untracked())
+        .expect("new channels aren't closed");
+
+    // `true` while a value from Bevy is written to the signal which must not be sent back
+    let applying_from_bevy = Arc::new(AtomicBool::new(false));
+    let dirty = Arc::new(AtomicBool::new(false));
+    let initialized = AtomicBool::new(false);
+
+    let effect = ImmediateEffect::new_isomorphic({
+        let applying_from_bevy = Arc::clone(&applying_from_bevy);
+
+        move || {
+            signal.track();
+
+            if !initialized.swap(true, Ordering::Relaxed)
+                || applying_from_bevy.load(Ordering::Relaxed)
+            {
+                return;
             }
-        });
-    }
 
-    #[cfg(not(target_arch = "wasm32"))]
-    {
-        let _ = rx_b2l;
-    }
+            // all the writes of a frame are sent together once at the next frame or when the
+            // transaction is committed
+            if !dirty.swap(true, Ordering::Relaxed) || in_transaction() {
+                let dirty = Arc::clone(&dirty);
+                let tx_l2b = tx_l2b.clone();
+
+                on_next_frame_or_commit(Arc::as_ptr(&dirty) as usize, move || {
+                    // the value has already been sent when a transaction has been committed
+                    if !dirty.swap(false, Ordering::Relaxed) {
+                        return;
+                    }
+
+                    if let Some(value) = signal.try_get_untracked() {
+                        // only fails once the channel has been closed
+                        let _ = send_to_bevy(&tx_l2b, value);
+                    }
+                });
+            }
+        }
+    });
+
+    on_cleanup(move || drop(effect));
+
+    use_frame_fn(move || {
+        for message in rx_b2l.try_iter() {
+            applying_from_bevy.store(true, Ordering::Relaxed);
+            signal.update(|value| *value = message);
+            applying_from_bevy.store(false, Ordering::Relaxed);
+        }
+    });
+
+    BevyMessageDuplex::new(rx_l2b, tx_b2l)
+}
+
+/// Same as [`signal_synced`] but only the changes are sent between Leptos and Bevy instead of
+/// the whole value. Use this for large values together with
+/// [`LeptosBevyApp::sync_leptos_signal_with_diffed_resource`](crate::prelude::LeptosBevyApp::sync_leptos_signal_with_diffed_resource).
+///
+/// If a patch from Bevy can't be applied a snapshot of the whole value is requested from Bevy.
+/// If both sides changed the value at the same time Bevy rejects the patch from Leptos and
+/// answers with a snapshot. See [`SyncUpdate`].
+pub fn diffed_signal_synced<T>(
+    initial_value: T,
+) -> (RwSignalSynced<T>, BevyMessageDuplex<SyncUpdate<T>>)
+where
+    T: Diffable,
+{
+    let (tx_l2b, rx_l2b) = crate::channel::bounded(50);
+    let (tx_b2l, rx_b2l) = crate::channel::bounded::<SyncUpdate<T>>(50);
+
+    close_on_cleanup(&tx_l2b);
+    close_on_cleanup(&tx_b2l);
+
+    tx_l2b
+        .send(SyncUpdate::Snapshot {
+            version: 0,
+            value: initial_value.clone(),
+        })
+        .expect("new channels aren't closed");
+
+    let signal = RwSignal::new(initial_value.clone());
+    let observers = Observers::default();
+
+    let synced = Arc::new(Mutex::new(Synced {
+        value: initial_value,
+        version: 0,
+        retrying: false,
+    }));
+
+    use_frame_fn({
+        let synced = Arc::clone(&synced);
+        let tx_l2b = tx_l2b.clone();
+
+        move || {
+            for update in rx_b2l.try_iter() {
+                let mut synced = synced.lock().expect("Lock poisoned");
+
+                match update {
+                    SyncUpdate::Snapshot { version, value } => {
+                        synced.value = value.clone();
+                        synced.version = version;
+                        signal.set(value);
+                    }
+                    SyncUpdate::Patch { base, patch } => {
+                        // Leptos has sent a patch of its own in the meantime which Bevy rejects
+                        // and answers with a snapshot
+                        if base < synced.version {
+                            continue;
+                        }
+
+                        if base > synced.version || synced.value.apply_patch(patch.clone()).is_err()
+                        {
+                            request_resync(tx_l2b.clone());
+                            continue;
+                        }
+
+                        synced.version += 1;
+
+                        // Leptos writes that haven't been sent yet are kept if the patch fits
+                        let applied = signal
+                            .try_update(|value| value.apply_patch(patch).is_ok())
+                            .unwrap_or_default();
+
+                        if !applied {
+                            signal.set(synced.value.clone());
+                        }
+                    }
+                    SyncUpdate::Resync => {}
+                }
+            }
+        }
+    });
 
     (
         RwSignalSynced {
             rw_signal: signal,
-            tx: StoredValue::new(tx_l2b),
+            send: StoredValue::new(Arc::new(move || {
+                send_patch(signal, Arc::clone(&synced), tx_l2b.clone());
+            })),
+            dirty: StoredValue::new(false),
+            observers: StoredValue::new(observers.clone()),
         },
-        BevyMessageDuplex::new(rx_l2b, tx_b2l),
+        BevyMessageDuplex::new(rx_l2b, tx_b2l).with_observers(observers),
     )
 }
+
+/// The last value that Leptos and Bevy agreed on in a diffed sync.
+struct Synced<T> {
+    value: T,
+    version: u64,
+    /// `true` while a patch couldn't be sent and is retried on the next frame
+    retrying: bool,
+}
+
+/// Sends the changes of the signal since the last synced value to Bevy.
+///
+/// If the channel is full because Bevy doesn't read it right now (e.g. while a run condition is
+/// `false`) the synced value is kept as it is and the send is retried on the next frame with the
+/// latest value of the signal. This way the patch that is finally sent contains all the changes.
+fn send_patch<T: Diffable>(
+    signal: RwSignal<T>,
+    synced: Arc<Mutex<Synced<T>>>,
+    tx_l2b: Sender<SyncUpdate<T>>,
+) {
+    let mut guard = synced.lock().expect("Lock poisoned");
+
+    let Some(Some(patch)) = signal.try_with_untracked(|value| value.diff(&guard.value)) else {
+        return;
+    };
+
+    let base = guard.version;
+
+    match send_to_bevy(
+        &tx_l2b,
+        SyncUpdate::Patch {
+            base,
+            patch: patch.clone(),
+        },
+    ) {
+        Ok(()) => {
+            // a patch always fits the value it has been created from
+            if guard.value.apply_patch(patch).is_err() {
+                if let Some(value) = signal.try_get_untracked() {
+                    guard.value = value;
+                }
+            }
+            guard.version += 1;
+        }
+        Err(TrySendError::Full(_)) if !guard.retrying => {
+            guard.retrying = true;
+            drop(guard);
+
+            on_next_frame(move || {
+                synced.lock().expect("Lock poisoned").retrying = false;
+                send_patch(signal, synced, tx_l2b);
+            });
+        }
+        // already retrying or the channel has been closed and the patch isn't needed anymore
+        Err(TrySendError::Full(_) | TrySendError::Disconnected(_)) => {}
+    }
+}
+
+/// Asks Bevy for a snapshot. Retried on the next frame if the channel is full.
+fn request_resync<T: Diffable>(tx_l2b: Sender<SyncUpdate<T>>) {
+    if let Err(TrySendError::Full(_)) = tx_l2b.try_send(SyncUpdate::Resync) {
+        on_next_frame(move || request_resync(tx_l2b));
+    }
+}
+
+/// Same as [`signal_synced`] but the initial value is shared between the server and the client.
+///
+/// On the server `initial_value` is called and the value is serialized into the page. During
+/// hydration on the client the value is read from the page instead of calling `initial_value`
+/// again.
+#[cfg(any(feature = "ssr", feature = "hydrate"))]
+pub fn shared_signal_synced<T>(
+    initial_value: impl FnOnce() -> T,
+) -> (RwSignalSynced<T>, BevyMessageDuplex<T>)
+where
+    T: serde::Serialize + serde::de::DeserializeOwned + Send + Sync + Clone + 'static,
+{
+    signal_synced(SharedValue::new(initial_value).into_inner())
+}
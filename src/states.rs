@@ -0,0 +1,74 @@
+use crate::messages::BevyMessageSender;
+use crate::utils::{close_on_cleanup, init_rw_signal_from_receiver};
+use leptos::prelude::*;
+
+/// Read-only synchronization for Bevy states of any kind including `ComputedStates` and
+/// `SubStates`.
+///
+/// Creates a pair of a read-only `Signal` and a `BevyMessageSender` for a Bevy state. Pass the
+/// sender to
+/// [`LeptosBevyApp::export_state_to_leptos`](crate::prelude::LeptosBevyApp::export_state_to_leptos).
+///
+/// The signal is `None` as long as the state doesn't exist in Bevy, e.g. until Bevy has sent it
+/// for the first time or while the source states of a computed state don't compute it.
+///
+/// To receive every transition instead (e.g. for animations or analytics) use
+/// `message_b2l::<StateTransitionEvent<S>>()` together with
+/// [`LeptosBevyApp::export_state_transitions_to_leptos`](crate::prelude::LeptosBevyApp::export_state_transitions_to_leptos)
+/// and register a callback with
+/// [`on_message`](crate::prelude::LeptosChannelMessageReceiver::on_message). Reading the receiver
+/// like a signal only gives you the last transition if several arrive in the same frame.
+///
+/// ## Example
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use bevy::state::app::StatesPlugin;
+/// # use leptos_bevy_canvas::prelude::*;
+/// #
+/// #[derive(States, Clone, Debug, PartialEq, Eq, Hash, Default)]
+/// enum AppState {
+///     #[default]
+///     Menu,
+///     InGame { paused: bool },
+/// }
+///
+/// #[derive(Clone, Debug, PartialEq, Eq, Hash)]
+/// struct Paused;
+///
+/// impl ComputedStates for Paused {
+///     type SourceStates = AppState;
+///
+///     fn compute(sources: AppState) -> Option<Self> {
+///         matches!(sources, AppState::InGame { paused: true }).then_some(Paused)
+///     }
+/// }
+///
+/// let (paused, bevy_paused_sender) = state_signal::<Paused>();
+/// let (transitions, bevy_transitions_sender) = message_b2l::<StateTransitionEvent<AppState>>();
+/// transitions.on_message(|transition| {
+///     // called once for every transition
+/// });
+///
+/// let mut app = App::new();
+/// app.add_plugins(StatesPlugin)
+///     .init_state::<AppState>()
+///     .add_computed_state::<Paused>()
+///     .export_state_to_leptos(bevy_paused_sender)
+///     .export_state_transitions_to_leptos(bevy_transitions_sender);
+/// ```
+pub fn state_signal<S>() -> (Signal<Option<S>>, BevyMessageSender<Option<S>>)
+where
+    S: Clone + Send + Sync + 'static,
+{
+    let (tx, rx) = crate::channel::latest();
+
+    close_on_cleanup(&tx);
+
+    let signal = init_rw_signal_from_receiver(&rx);
+
+    (
+        Signal::derive(move || signal.get().flatten()),
+        BevyMessageSender::new(tx),
+    )
+}
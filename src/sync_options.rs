@@ -0,0 +1,514 @@
+use crate::history::BevyHistoryChannel;
+#[cfg(feature = "persist")]
+use crate::persist::{Persistence, SyncStorage};
+#[cfg(feature = "record")]
+use crate::record::RecordChannel;
+use bevy::ecs::schedule::{BoxedCondition, InternedScheduleLabel, ScheduleConfigs, ScheduleLabel};
+use bevy::ecs::system::ScheduleSystem;
+use bevy::prelude::*;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Decides which value is kept when both Bevy and Leptos changed a synced value in the same frame.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum SyncPolicy {
+    /// The Bevy value is kept and sent back to Leptos which rolls back the Leptos write.
+    BevyWins,
+    /// The Leptos value is applied in Bevy.
+    #[default]
+    LeptosWins,
+    /// The value that has been written last is kept. Leptos writes happen between Bevy frames
+    /// so a Bevy change that has been made in the current frame before the sync system ran wins
+    /// over the Leptos write while a change from the previous frame loses.
+    LastWriterWins,
+}
+
+/// Returned by a validator to tell what happened to a value written from Leptos.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Validation {
+    /// The value is applied as is.
+    Valid,
+    /// The validator modified the value (e.g. clamped it). The corrected value is applied and
+    /// sent back to Leptos.
+    Corrected,
+    /// The value is discarded and the current Bevy value is sent back to Leptos.
+    Invalid,
+}
+
+type Validator<T> = Arc<dyn Fn(&T, &mut T) -> Validation + Send + Sync>;
+
+/// Creates a new instance of a run condition for every registration of the sync system.
+type RunCondition = Arc<dyn Fn() -> BoxedCondition + Send + Sync>;
+
+/// An option that not every kind of sync supports.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum SyncFeature {
+    Policy,
+    Validator,
+    History,
+    #[cfg(feature = "persist")]
+    Persist,
+    MaxSendRate,
+    SendSchedule,
+}
+
+/// Options for syncing a Leptos signal with a Bevy resource, state or query.
+///
+/// Not every kind of sync supports every option. Adding a sync with an option it doesn't support
+/// panics instead of ignoring the option.
+///
+/// | Option                      | Resource | State | Query | Optional resource, field, diffed |
+/// |-----------------------------|----------|-------|-------|----------------------------------|
+/// | `policy`, `validator`       | ✓        | ✓     |       |                                  |
+/// | `history`                   | ✓        |       |       |                                  |
+/// | `persist`                   | ✓        | ✓     |       |                                  |
+/// | `max_send_rate`             | ✓        | ✓     | ✓     |                                  |
+/// | run conditions, `schedule`  | ✓        | ✓     | ✓     | ✓                                |
+/// | `send_schedule`             | ✓        |       | ✓     | ✓                                |
+/// | `record`                    | ✓        | ✓     | ✓     | ✓                                |
+#[derive(Resource)]
+pub struct SyncOptions<T> {
+    policy: SyncPolicy,
+    validator: Option<Validator<T>>,
+    history: Option<(BevyHistoryChannel, usize)>,
+    min_send_interval: Option<Duration>,
+    run_conditions: Vec<RunCondition>,
+    schedule: Option<InternedScheduleLabel>,
+    send_schedule: Option<InternedScheduleLabel>,
+    #[cfg(feature = "persist")]
+    persistence: Option<Persistence<T>>,
+    #[cfg(feature = "record")]
+    record_channel: Option<RecordChannel>,
+}
+
+impl<T> Default for SyncOptions<T> {
+    fn default() -> Self {
+        Self {
+            policy: SyncPolicy::default(),
+            validator: None,
+            history: None,
+            min_send_interval: None,
+            run_conditions: Vec::new(),
+            schedule: None,
+            send_schedule: None,
+            #[cfg(feature = "persist")]
+            persistence: None,
+            #[cfg(feature = "record")]
+            record_channel: None,
+        }
+    }
+}
+
+impl<T> Clone for SyncOptions<T> {
+    fn clone(&self) -> Self {
+        Self {
+            policy: self.policy,
+            validator: self.validator.clone(),
+            history: self.history.clone(),
+            min_send_interval: self.min_send_interval,
+            run_conditions: self.run_conditions.clone(),
+            schedule: self.schedule,
+            send_schedule: self.send_schedule,
+            #[cfg(feature = "persist")]
+            persistence: self.persistence.clone(),
+            #[cfg(feature = "record")]
+            record_channel: self.record_channel.clone(),
+        }
+    }
+}
+
+impl<T> SyncOptions<T> {
+    /// Decides which value is kept when both Bevy and Leptos changed the value in the same frame.
+    /// Defaults to [`SyncPolicy::LeptosWins`]. Only supported for resources and states.
+    pub fn policy(mut self, policy: SyncPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Is called in Bevy for every value written from Leptos with the current Bevy value and the
+    /// new value. It can modify the new value or reject it. See [`Validation`]. Only supported
+    /// for resources and states.
+    pub fn validator(
+        mut self,
+        validator: impl Fn(&T, &mut T) -> Validation + Send + Sync + 'static,
+    ) -> Self {
+        self.validator = Some(Arc::new(validator));
+        self
+    }
+
+    /// Keeps an undo/redo history of at most `depth` entries that can be controlled from Leptos
+    /// with the [`HistoryControls`](crate::prelude::HistoryControls) created together with
+    /// `bevy_history` by [`history_controls`](crate::prelude::history_controls). Only supported
+    /// for resources. In Bevy the history is available as the resource
+    /// [`History<T>`](crate::prelude::History).
+    pub fn history(mut self, bevy_history: BevyHistoryChannel, depth: usize) -> Self {
+        self.history = Some((bevy_history, depth));
+        self
+    }
+
+    /// Sends the value from Bevy to Leptos at most `hz` times per second. Changes in between
+    /// aren't sent but once enough time has passed the latest value is sent. Values written in
+    /// Leptos are still applied in every frame. Only supported for resources, states and
+    /// queries.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use leptos_bevy_canvas::prelude::*;
+    /// #
+    /// #[derive(Resource, Clone, Default)]
+    /// struct PlayerPosition(Vec3);
+    ///
+    /// # let (position, bevy_position) = signal_synced(PlayerPosition::default());
+    /// let mut app = App::new();
+    /// app.sync_leptos_signal_with_resource_with_options(
+    ///     bevy_position,
+    ///     SyncOptions::default().max_send_rate(10.0),
+    /// );
+    /// ```
+    pub fn max_send_rate(mut self, hz: f64) -> Self {
+        self.min_send_interval = (hz > 0.0).then(|| Duration::from_secs_f64(1.0 / hz));
+        self
+    }
+
+    /// Only runs the sync if `condition` is `true`. While the sync doesn't run nothing is sent
+    /// to Leptos and values written in Leptos are applied once it runs again. Can be called
+    /// multiple times in which case all the conditions have to be `true`.
+    ///
+    /// Like Bevy's `distributive_run_if` the condition has to be `Clone`.
+    pub fn run_if<M>(
+        mut self,
+        condition: impl SystemCondition<M> + Clone + Send + Sync + 'static,
+    ) -> Self {
+        self.run_conditions.push(Arc::new(move || {
+            Box::new(IntoSystem::into_system(condition.clone()))
+        }));
+        self
+    }
+
+    /// Only runs the sync while the Bevy state `S` is `state`. This is the same as
+    /// `.run_if(in_state(state))`.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use leptos_bevy_canvas::prelude::*;
+    /// #
+    /// #[derive(States, Clone, Debug, Default, PartialEq, Eq, Hash)]
+    /// enum AppState {
+    ///     #[default]
+    ///     Loading,
+    ///     Ready,
+    /// }
+    ///
+    /// #[derive(Resource, Clone, Default)]
+    /// struct Score(u32);
+    ///
+    /// # let (score, bevy_score) = signal_synced(Score::default());
+    /// let mut app = App::new();
+    /// app.sync_leptos_signal_with_resource_with_options(
+    ///     bevy_score,
+    ///     SyncOptions::default().in_state(AppState::Ready),
+    /// );
+    /// ```
+    #[cfg(feature = "bevy_state")]
+    pub fn in_state<S>(self, state: S) -> Self
+    where
+        S: bevy::state::state::States,
+    {
+        self.run_if(bevy::state::condition::in_state(state))
+    }
+
+    /// The schedule in which the values written in Leptos are applied in Bevy. Defaults to
+    /// `PreUpdate` so that your systems in `Update` always see them. States are always synced
+    /// in this schedule.
+    ///
+    /// The system is part of the same system set as usual (e.g.
+    /// [`SyncQuerySet`](crate::systems::SyncQuerySet)) so it can be ordered relative to other
+    /// systems in that schedule.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use leptos_bevy_canvas::prelude::*;
+    /// # use leptos_bevy_canvas::systems::SyncQuerySet;
+    /// #
+    /// # #[derive(Component)]
+    /// # struct Selected;
+    /// #
+    /// #[derive(Component, Clone)]
+    /// struct Health(f32);
+    ///
+    /// fn apply_damage(mut query: Query<&mut Health>) {
+    ///     // ...
+    /// }
+    ///
+    /// # let (selected, bevy_selected) = single_query_signal::<(Health,), With<Selected>>();
+    /// let mut app = App::new();
+    /// app.sync_leptos_signal_with_query_with_options(
+    ///     bevy_selected,
+    ///     SyncOptions::default().schedule(FixedPreUpdate),
+    /// )
+    /// .add_systems(FixedPreUpdate, apply_damage)
+    /// .configure_sets(FixedPreUpdate, SyncQuerySet.before(apply_damage));
+    /// ```
+    pub fn schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.schedule = Some(schedule.intern());
+        self
+    }
+
+    /// The schedule in which the changes of Bevy are sent to Leptos. Defaults to `PostUpdate`
+    /// so that the changes of your systems in `Update` are sent in the same frame. Like
+    /// [`SyncOptions::schedule`] the system is part of the usual system set. Not supported for
+    /// states.
+    pub fn send_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.send_schedule = Some(schedule.intern());
+        self
+    }
+
+    /// Persists the value in `storage` under `key`. The persisted value is loaded before the
+    /// first frame of the Bevy app and ignored if the [`SyncOptions::validator`] rejects it. Only
+    /// supported for resources and states. See the [`persist`](crate::persist) module.
+    #[cfg(feature = "persist")]
+    pub fn persist(mut self, storage: impl SyncStorage, key: impl Into<String>) -> Self
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned,
+    {
+        self.persistence = Some(Persistence::new(storage, key.into()));
+        self
+    }
+
+    /// The version of the persisted value. Values that have been persisted with a different
+    /// version are ignored. Increase it when the persisted type changes incompatibly. Has no
+    /// effect if [`SyncOptions::persist`] hasn't been called before. Defaults to `0`.
+    #[cfg(feature = "persist")]
+    pub fn persist_version(mut self, version: u32) -> Self {
+        if let Some(persistence) = &mut self.persistence {
+            persistence.set_version(version);
+        }
+        self
+    }
+
+    /// Records the values that cross this sync under the name `channel` and replays them from a
+    /// `BridgeReplay`. The name has to be unique in the app. See the [`record`](crate::record)
+    /// module.
+    #[cfg(feature = "record")]
+    pub fn record(mut self, channel: impl Into<String>) -> Self
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned + 'static,
+    {
+        self.record_channel = Some(RecordChannel::new::<T>(channel.into()));
+        self
+    }
+}
+
+impl<T> SyncOptions<T> {
+    /// Panics if an option is set that the sync `sync` doesn't support so it isn't ignored
+    /// silently.
+    pub(crate) fn assert_supported(&self, sync: &str, supported: &[SyncFeature]) {
+        let set = [
+            (
+                SyncFeature::Policy,
+                "policy",
+                self.policy != SyncPolicy::default(),
+            ),
+            (
+                SyncFeature::Validator,
+                "validator",
+                self.validator.is_some(),
+            ),
+            (SyncFeature::History, "history", self.history.is_some()),
+            (
+                SyncFeature::MaxSendRate,
+                "max_send_rate",
+                self.min_send_interval.is_some(),
+            ),
+            (
+                SyncFeature::SendSchedule,
+                "send_schedule",
+                self.send_schedule.is_some(),
+            ),
+            #[cfg(feature = "persist")]
+            (SyncFeature::Persist, "persist", self.persistence.is_some()),
+        ];
+
+        for (feature, name, is_set) in set {
+            assert!(
+                !is_set || supported.contains(&feature),
+                "`SyncOptions::{name}` isn't supported by `{sync}`"
+            );
+        }
+    }
+
+    /// Runs the validator if there is one.
+    pub(crate) fn validate(&self, current: &T, value: &mut T) -> Validation {
+        self.validator
+            .as_ref()
+            .map_or(Validation::Valid, |validator| validator(current, value))
+    }
+
+    pub(crate) fn history_channel(&self) -> Option<(BevyHistoryChannel, usize)> {
+        self.history.clone()
+    }
+
+    /// The schedule that has been set with [`SyncOptions::schedule`] or `default`.
+    pub(crate) fn schedule_or(&self, default: impl ScheduleLabel) -> InternedScheduleLabel {
+        self.schedule.unwrap_or_else(|| default.intern())
+    }
+
+    /// The schedule that has been set with [`SyncOptions::send_schedule`] or `PostUpdate`.
+    pub(crate) fn send_schedule_or_default(&self) -> InternedScheduleLabel {
+        self.send_schedule.unwrap_or_else(|| PostUpdate.intern())
+    }
+
+    pub(crate) fn min_send_interval(&self) -> Option<Duration> {
+        self.min_send_interval
+    }
+
+    /// Adds the run conditions to the configs of a sync system.
+    pub(crate) fn apply_run_conditions(
+        &self,
+        mut configs: ScheduleConfigs<ScheduleSystem>,
+    ) -> ScheduleConfigs<ScheduleSystem> {
+        for condition in &self.run_conditions {
+            configs.run_if_dyn(condition());
+        }
+
+        configs
+    }
+
+    #[cfg(feature = "persist")]
+    pub(crate) fn persistence(&self) -> Option<&Persistence<T>> {
+        self.persistence.as_ref()
+    }
+
+    #[cfg(feature = "record")]
+    pub(crate) fn record_channel(&self) -> Option<&RecordChannel> {
+        self.record_channel.as_ref()
+    }
+}
+
+/// Options for importing messages from Leptos and exporting messages to Leptos.
+#[derive(Clone, Debug, Default)]
+pub struct MessageOptions {
+    import_schedule: Option<InternedScheduleLabel>,
+    export_schedule: Option<InternedScheduleLabel>,
+    #[cfg(feature = "record")]
+    record_channel: Option<RecordChannel>,
+}
+
+impl MessageOptions {
+    /// The schedule in which the messages from Leptos are written as Bevy messages. Defaults to
+    /// `PreUpdate`.
+    pub fn import_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.import_schedule = Some(schedule.intern());
+        self
+    }
+
+    /// The schedule in which the Bevy messages are read and sent to Leptos. Also used by
+    /// exported resources and states. Defaults to `PostUpdate`.
+    pub fn export_schedule(mut self, schedule: impl ScheduleLabel) -> Self {
+        self.export_schedule = Some(schedule.intern());
+        self
+    }
+
+    /// Imports the messages from Leptos in `FixedPreUpdate` so they can be read by systems in
+    /// `FixedUpdate`.
+    ///
+    /// Every message is written in exactly one fixed tick: the first one after it has been sent
+    /// from Leptos. If several fixed ticks run in one frame only the first one receives the
+    /// messages and if no fixed tick runs in a frame the messages are kept until the next one.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use leptos_bevy_canvas::prelude::*;
+    /// #
+    /// #[derive(Message)]
+    /// struct Jump;
+    ///
+    /// fn jump(mut jumps: MessageReader<Jump>) {
+    ///     for _ in jumps.read() {
+    ///         // apply an impulse
+    ///     }
+    /// }
+    ///
+    /// # let (jump_sender, bevy_jump_receiver) = message_l2b::<Jump>();
+    /// let mut app = App::new();
+    /// app.import_message_from_leptos_with_options(
+    ///     bevy_jump_receiver,
+    ///     MessageOptions::default().fixed_update(),
+    /// )
+    /// .add_systems(FixedUpdate, jump);
+    /// ```
+    pub fn fixed_update(self) -> Self {
+        self.import_schedule(FixedPreUpdate)
+    }
+
+    /// Records the values of type `M` that cross this bridge under the name `channel` and
+    /// replays the ones from Leptos from a `BridgeReplay` in the import schedule. The name has to
+    /// be unique in the app. See the [`record`](crate::record) module.
+    ///
+    /// `M` is the type that the bridge carries, e.g. `Option<S>` for an exported state. Adding
+    /// the bridge panics if it doesn't match.
+    #[cfg(feature = "record")]
+    pub fn record<M>(mut self, channel: impl Into<String>) -> Self
+    where
+        M: serde::Serialize + serde::de::DeserializeOwned + 'static,
+    {
+        self.record_channel = Some(RecordChannel::new::<M>(channel.into()));
+        self
+    }
+
+    #[cfg(feature = "record")]
+    pub(crate) fn record_channel(&self) -> Option<&RecordChannel> {
+        self.record_channel.as_ref()
+    }
+
+    pub(crate) fn import_schedule_or_default(&self) -> InternedScheduleLabel {
+        self.import_schedule.unwrap_or_else(|| PreUpdate.intern())
+    }
+
+    pub(crate) fn export_schedule_or_default(&self) -> InternedScheduleLabel {
+        self.export_schedule.unwrap_or_else(|| PostUpdate.intern())
+    }
+}
+
+/// What should be done with a value that has been written from Leptos.
+pub(crate) enum Resolved<T> {
+    /// Apply the value. If the flag is `true` the value has to be sent back to Leptos.
+    Apply(T, bool),
+    /// Keep the current Bevy value and send it back to Leptos.
+    Keep,
+}
+
+/// Resolves conflicts and validates a value that has been written from Leptos.
+///
+/// `conflict` is `None` if Bevy didn't change the value since the last sync. Otherwise it
+/// tells if the Bevy change is newer than the Leptos write.
+pub(crate) fn resolve_leptos_value<T>(
+    options: Option<&SyncOptions<T>>,
+    current: &T,
+    mut value: T,
+    conflict: Option<bool>,
+) -> Resolved<T> {
+    if let Some(bevy_is_newer) = conflict {
+        let policy = options.map(|options| options.policy).unwrap_or_default();
+
+        let leptos_wins = match policy {
+            SyncPolicy::BevyWins => false,
+            SyncPolicy::LeptosWins => true,
+            SyncPolicy::LastWriterWins => !bevy_is_newer,
+        };
+
+        if !leptos_wins {
+            return Resolved::Keep;
+        }
+    }
+
+    let validation = options.map_or(Validation::Valid, |options| {
+        options.validate(current, &mut value)
+    });
+
+    match validation {
+        Validation::Valid => Resolved::Apply(value, false),
+        Validation::Corrected => Resolved::Apply(value, true),
+        Validation::Invalid => Resolved::Keep,
+    }
+}
@@ -1,9 +1,20 @@
-use crate::messages::BevyMessageDuplex;
+use crate::channel::{Receiver, Sender};
+use crate::diff::{Diffable, SyncUpdate};
+use crate::messages::{BevyMessageDuplex, ToLeptos};
 use crate::prelude::QueryDataOwned;
+#[cfg(feature = "record")]
+use crate::record::{BridgeDirection, BridgeRecording};
+use crate::sync_options::{resolve_leptos_value, Resolved, SyncOptions};
 use crate::traits::{HasReceiver, HasSender};
+use crate::utils::PendingSend;
+use bevy::ecs::change_detection::Tick;
 use bevy::ecs::message::MessageId;
 use bevy::ecs::query::QueryFilter;
+use bevy::ecs::system::SystemChangeTick;
 use bevy::prelude::*;
+use std::marker::PhantomData;
+use std::sync::{Mutex, PoisonError};
+use std::time::Duration;
 
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone, Copy)]
 pub struct SyncSignalResourceSet;
@@ -21,6 +32,16 @@ pub struct ExportLeptosMessageSet;
 #[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone, Copy)]
 pub struct SyncQuerySet;
 
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub struct ExportResourceSet;
+
+#[cfg(feature = "bevy_state")]
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub struct ExportStateSet;
+
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub struct RemoveClosedBridgesSet;
+
 /// Keeps track of what Leptos message have been imported into Bevy to prevent infinite loops.
 #[derive(Resource, Deref, DerefMut)]
 pub struct ImportedMessageIds<M: Message>(Vec<MessageId<M>>);
@@ -31,18 +52,206 @@ impl<M: Message> Default for ImportedMessageIds<M> {
     }
 }
 
+/// The tick at the end of the last Bevy frame. Everything that Leptos writes happens after it.
+#[derive(Resource, Default, Deref)]
+pub struct LeptosFrameEndTick(Tick);
+
+impl LeptosFrameEndTick {
+    /// Returns `true` if `last_changed` is after the end of the last frame.
+    pub fn changed_this_frame(&self, last_changed: Tick, this_run: Tick) -> bool {
+        last_changed.is_newer_than(self.0, this_run)
+    }
+}
+
+/// Stores the tick at the end of the frame into [`LeptosFrameEndTick`].
+pub fn update_frame_end_tick(mut frame_end: ResMut<LeptosFrameEndTick>, ticks: SystemChangeTick) {
+    frame_end.0 = ticks.this_run();
+}
+
+/// Collects everything that is sent from Bevy to Leptos during a frame. It is flushed at the end
+/// of the frame by [`flush_leptos_outbox`] so Leptos always applies all the updates of a Bevy
+/// frame together.
+#[derive(Resource, Default)]
+pub struct LeptosOutbox {
+    sends: Mutex<Vec<Box<dyn PendingSend>>>,
+    /// Values that couldn't be sent because their channel was full. They are sent first in the
+    /// next frame.
+    leftovers: Mutex<Vec<Box<dyn PendingSend>>>,
+}
+
+impl LeptosOutbox {
+    /// Queues `value` to be sent over `tx` at the end of the frame.
+    pub fn send<T: Send + 'static>(&self, tx: &Sender<T>, value: T) {
+        self.sends
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Box::new((tx.clone(), Some(value))));
+    }
+}
+
+#[derive(SystemSet, Debug, Hash, PartialEq, Eq, Clone, Copy)]
+pub struct FlushLeptosOutboxSet;
+
+/// Sends everything in the [`LeptosOutbox`] to Leptos while the bridge is locked so Leptos
+/// doesn't apply a partial frame.
+///
+/// This never blocks on a full channel because Leptos can't drain it while the bridge is locked
+/// (and runs on the same thread in the browser). The values that don't fit are kept together
+/// with the later values of the same channel and sent in the next frame.
+pub fn flush_leptos_outbox(mut outbox: ResMut<LeptosOutbox>) {
+    let outbox = &mut *outbox;
+    let leftovers = outbox
+        .leftovers
+        .get_mut()
+        .unwrap_or_else(PoisonError::into_inner);
+
+    let mut sends = std::mem::take(leftovers);
+    sends.append(
+        outbox
+            .sends
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner),
+    );
+
+    if sends.is_empty() {
+        return;
+    }
+
+    let mut bridges = sends
+        .iter()
+        .map(|pending| pending.bridge_lock().clone())
+        .collect::<Vec<_>>();
+    bridges.sort_by_key(|bridge| bridge.id());
+    bridges.dedup_by_key(|bridge| bridge.id());
+
+    let _guards = bridges
+        .iter()
+        .map(|bridge| bridge.lock())
+        .collect::<Vec<_>>();
+
+    let mut full_channels = Vec::new();
+
+    for mut pending in sends {
+        let channel = pending.channel_id();
+
+        if full_channels.contains(&channel) || !pending.try_send() {
+            full_channels.push(channel);
+            leftovers.push(pending);
+        }
+    }
+
+    if !leftovers.is_empty() {
+        warn!(
+            "A channel to Leptos is full. {} values are sent in a later frame.",
+            leftovers.len()
+        );
+    }
+}
+
+/// Remembers that a synced value has to be sent to Leptos until it actually can be sent, i.e.
+/// the signal is observed and the minimum send interval has passed.
+#[derive(Default)]
+pub struct SendThrottle {
+    /// `true` if the value changed since it has been sent last
+    pending: bool,
+    last_sent: Option<Duration>,
+}
+
+impl SendThrottle {
+    /// Marks the value as changed.
+    pub fn mark(&mut self) {
+        self.pending = true;
+    }
+
+    /// Returns `true` if the value changed and should be sent now. `now` is the elapsed real
+    /// time of the app.
+    pub fn ready(&mut self, min_interval: Option<Duration>, now: Option<Duration>) -> bool {
+        if !self.pending {
+            return false;
+        }
+
+        if let (Some(min_interval), Some(now), Some(last_sent)) =
+            (min_interval, now, self.last_sent)
+        {
+            if now.saturating_sub(last_sent) < min_interval {
+                return false;
+            }
+        }
+
+        self.sent(now);
+
+        true
+    }
+
+    /// Returns `true` if the value changed since it has been sent last.
+    pub fn is_pending(&self) -> bool {
+        self.pending
+    }
+
+    /// Remembers that the value has been sent at `now` regardless of the minimum interval.
+    pub fn sent(&mut self, now: Option<Duration>) {
+        self.pending = false;
+        self.last_sent = now;
+    }
+}
+
+/// Clears the [`ImportedMessageIds`] at the start of a frame. They are collected over the whole
+/// frame because messages might be imported in several fixed ticks.
+pub fn clear_imported_message_ids<M: Message>(
+    mut imported_message_ids: ResMut<ImportedMessageIds<M>>,
+) {
+    imported_message_ids.clear();
+}
+
+/// Removes the bridge resource `R` once Leptos has closed it, i.e. when the reactive owner that
+/// created the Leptos side has been disposed. The systems of a bridge only run while its resource
+/// exists. See [`attach_leptos_bridges`] to attach a new Leptos signal again.
+pub fn remove_closed_receiver<R, M>(rx: Res<R>, mut commands: Commands)
+where
+    R: HasReceiver<M> + Resource,
+    M: Send + Sync + 'static,
+{
+    if rx.rx().is_closed() {
+        commands.remove_resource::<R>();
+    }
+}
+
+/// Inserts the bridge resources that Leptos has sent to attach them to the app.
+pub fn attach_leptos_bridges<R, D>(rx: Res<R>, mut commands: Commands)
+where
+    R: HasReceiver<D> + Resource,
+    D: Resource,
+{
+    for bridge in rx.rx().try_iter() {
+        commands.insert_resource(bridge);
+    }
+}
+
+/// Same as [`remove_closed_receiver`] for bridges that only send to Leptos.
+pub fn remove_closed_sender<S, M>(tx: Res<S>, mut commands: Commands)
+where
+    S: HasSender<M> + Resource,
+    M: Send + Sync + 'static,
+{
+    if tx.tx().is_closed() {
+        commands.remove_resource::<S>();
+    }
+}
+
 /// Imports a message from Leptos and writes it as a Bevy message.
 pub fn import_and_send_leptos_messages<R, M>(
     rx: Res<R>,
     mut imported_message_ids: ResMut<ImportedMessageIds<M>>,
     mut message_writer: MessageWriter<M>,
+    #[cfg(feature = "record")] mut recording: BridgeRecording,
 ) where
     R: HasReceiver<M> + Resource,
     M: Message,
 {
-    imported_message_ids.clear();
-
     for message in rx.rx().try_iter() {
+        #[cfg(feature = "record")]
+        recording.record::<R, _>(BridgeDirection::LeptosToBevy, &message);
+
         let message_id = message_writer.write(message);
         imported_message_ids.push(message_id);
     }
@@ -53,75 +262,794 @@ pub fn read_and_export_leptos_messages<S, M>(
     tx: Res<S>,
     imported_message_ids: Res<ImportedMessageIds<M>>,
     mut message_reader: MessageReader<M>,
+    outbox: Res<LeptosOutbox>,
+    #[cfg(feature = "record")] mut recording: BridgeRecording,
 ) where
     S: HasSender<M> + Resource,
     M: Message + Clone,
 {
     for (message, id) in message_reader.read_with_id() {
         if !imported_message_ids.contains(&id) {
-            tx.tx().send(message.clone()).unwrap();
+            #[cfg(feature = "record")]
+            recording.record::<S, _>(BridgeDirection::BevyToLeptos, message);
+
+            outbox.send(tx.tx(), message.clone());
         }
     }
 }
 
-/// Takes care of synchronizing a resource between Bevy and a Leptos signal
-pub fn sync_signal_resource<D, R>(mut resource: ResMut<R>, sync: Res<D>)
-where
+/// Exports a message from Bevy to Leptos by draining all the [`ToLeptos`] messages so they don't
+/// have to be cloned.
+pub fn drain_and_export_leptos_messages<S, M>(
+    tx: Res<S>,
+    mut messages: ResMut<Messages<ToLeptos<M>>>,
+    outbox: Res<LeptosOutbox>,
+    #[cfg(feature = "record")] mut recording: BridgeRecording,
+) where
+    S: HasSender<M> + Resource,
+    M: Send + Sync + 'static,
+{
+    for ToLeptos(message) in messages.drain() {
+        #[cfg(feature = "record")]
+        recording.record::<S, _>(BridgeDirection::BevyToLeptos, &message);
+
+        outbox.send(tx.tx(), message);
+    }
+}
+
+/// The state of a sync that is shared by the system that applies the values from Leptos and the
+/// system that sends the changes of Bevy to Leptos. `D` is the bridge resource of the sync and
+/// `T` holds what else the kind of sync has to remember.
+#[derive(Resource)]
+pub struct SyncState<D, T = ()> {
+    /// The tick at which a value from Leptos has been applied last. A change at this tick isn't
+    /// sent back.
+    applied: Option<Tick>,
+    /// The tick at which the changes of Bevy have been checked last
+    checked: Tick,
+    throttle: SendThrottle,
+    /// `true` if the Bevy value has to be sent right away because Leptos wrote an outdated value
+    resync: bool,
+    inner: T,
+    marker: PhantomData<fn() -> D>,
+}
+
+impl<D, T: Default> SyncState<D, T> {
+    /// Creates the state of a sync that is added at `tick`. Changes before it aren't sent.
+    pub fn new(tick: Tick) -> Self {
+        Self {
+            applied: None,
+            checked: tick,
+            throttle: SendThrottle::default(),
+            resync: false,
+            inner: T::default(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<D, T> SyncState<D, T> {
+    /// Returns `true` if Bevy changed a value that has been changed last at `last_changed` since
+    /// the changes have been checked last.
+    fn bevy_changed(&self, last_changed: Tick, this_run: Tick) -> bool {
+        last_changed.is_newer_than(self.checked, this_run) && self.applied != Some(last_changed)
+    }
+}
+
+/// Applies the values written in Leptos to a synced resource. Runs before `Update` so your
+/// systems see the values. The changes of Bevy are sent by [`send_signal_resource`].
+pub fn apply_signal_resource<D, R>(
+    mut resource: ResMut<R>,
+    sync: Res<D>,
+    mut state: ResMut<SyncState<D>>,
+    options: Option<Res<SyncOptions<R>>>,
+    frame_end: Option<Res<LeptosFrameEndTick>>,
+    ticks: SystemChangeTick,
+    #[cfg(feature = "record")] mut recording: BridgeRecording,
+) where
     R: Resource + Clone,
     D: HasReceiver<R> + HasSender<R> + Resource,
 {
-    if resource.is_changed() && !resource.is_added() {
-        sync.tx().send(resource.clone()).unwrap();
-    }
+    let this_run = ticks.this_run();
 
+    // changed by Bevy since the changes have been sent, e.g. in `First`
+    let bevy_changed = state.bevy_changed(resource.last_changed(), this_run);
+
+    let mut leptos_value = None;
     for event in sync.rx().try_iter() {
-        *resource = event;
+        #[cfg(feature = "record")]
+        recording.record::<D, _>(BridgeDirection::LeptosToBevy, &event);
+
+        leptos_value = Some(event);
+    }
+
+    let Some(value) = leptos_value else {
+        if bevy_changed {
+            state.throttle.mark();
+        }
+        return;
+    };
+
+    // Leptos hasn't seen the changes Bevy made while the signal wasn't observed. A value written
+    // in Leptos is based on an outdated value and would overwrite them, so Bevy keeps its value
+    // and resyncs Leptos instead.
+    if !sync.is_observed() && (state.throttle.is_pending() || bevy_changed) {
+        state.resync = true;
+        return;
+    }
+
+    let conflict = bevy_changed.then(|| {
+        frame_end.is_some_and(|frame_end| {
+            frame_end.changed_this_frame(resource.last_changed(), this_run)
+        })
+    });
+
+    match resolve_leptos_value(options.as_deref(), &resource, value, conflict) {
+        Resolved::Apply(value, send_back) => {
+            *resource = value;
+            state.applied = Some(this_run);
+
+            if send_back {
+                state.throttle.mark();
+            }
+        }
+        Resolved::Keep => state.throttle.mark(),
     }
 }
 
-/// Takes care of synchronizing a state between Bevy and a Leptos signal
-#[cfg(feature = "bevy_state")]
-pub fn sync_signal_state<D, S>(mut state: ResMut<State<S>>, sync: Res<D>)
+/// Sends the changes of a synced resource to Leptos. Runs after `Update` so the changes of your
+/// systems are sent in the same frame.
+#[allow(clippy::too_many_arguments)]
+pub fn send_signal_resource<D, R>(
+    resource: Res<R>,
+    sync: Res<D>,
+    mut state: ResMut<SyncState<D>>,
+    options: Option<Res<SyncOptions<R>>>,
+    time: Option<Res<Time<Real>>>,
+    ticks: SystemChangeTick,
+    outbox: Res<LeptosOutbox>,
+    #[cfg(feature = "record")] mut recording: BridgeRecording,
+) where
+    R: Resource + Clone,
+    D: HasReceiver<R> + HasSender<R> + Resource,
+{
+    let state = &mut *state;
+
+    if state.bevy_changed(resource.last_changed(), ticks.this_run()) {
+        state.throttle.mark();
+    }
+    state.checked = ticks.this_run();
+
+    let resync = std::mem::take(&mut state.resync);
+
+    // a value that hasn't been sent because the signal wasn't observed is sent once it is again
+    if !resync && !sync.is_observed() {
+        return;
+    }
+
+    let min_interval = options.and_then(|options| options.min_send_interval());
+    let now = time.map(|time| time.elapsed());
+
+    if resync {
+        state.throttle.sent(now);
+    }
+
+    if resync || state.throttle.ready(min_interval, now) {
+        #[cfg(feature = "record")]
+        recording.record::<D, _>(BridgeDirection::BevyToLeptos, &*resource);
+
+        outbox.send(sync.tx(), resource.clone());
+    }
+}
+
+/// What the sync of an optional resource remembers.
+#[derive(Default)]
+pub struct OptionalResourceSync {
+    /// `true` if the resource existed when the changes have been checked last
+    prev_some: bool,
+    /// `true` if the resource has been inserted from Leptos in this frame
+    inserted_by_leptos: bool,
+}
+
+/// Applies the values written in Leptos to a synced optional resource. The resource is inserted
+/// or removed when the signal is set to `Some` or `None`. The changes of Bevy are sent by
+/// [`send_signal_optional_resource`].
+pub fn apply_signal_optional_resource<D, R>(
+    resource: Option<ResMut<R>>,
+    sync: Res<D>,
+    mut commands: Commands,
+    mut state: ResMut<SyncState<D, OptionalResourceSync>>,
+    ticks: SystemChangeTick,
+    #[cfg(feature = "record")] mut recording: BridgeRecording,
+) where
+    R: Resource + Clone,
+    D: HasReceiver<Option<R>> + HasSender<Option<R>> + Resource,
+{
+    let mut resource = resource;
+
+    for message in sync.rx().try_iter() {
+        #[cfg(feature = "record")]
+        recording.record::<D, _>(BridgeDirection::LeptosToBevy, &message);
+
+        match (message, &mut resource) {
+            (Some(message), Some(resource)) => {
+                **resource = message;
+                state.applied = Some(ticks.this_run());
+            }
+            (Some(message), None) => {
+                commands.insert_resource(message);
+                state.inner.prev_some = true;
+                state.inner.inserted_by_leptos = true;
+            }
+            (None, _) => {
+                commands.remove_resource::<R>();
+                resource = None;
+                state.inner.prev_some = false;
+            }
+        }
+    }
+}
+
+/// Sends the changes of a synced optional resource to Leptos. Sends `None` when it is removed.
+pub fn send_signal_optional_resource<D, R>(
+    resource: Option<Res<R>>,
+    sync: Res<D>,
+    mut state: ResMut<SyncState<D, OptionalResourceSync>>,
+    ticks: SystemChangeTick,
+    outbox: Res<LeptosOutbox>,
+    #[cfg(feature = "record")] mut recording: BridgeRecording,
+) where
+    R: Resource + Clone,
+    D: HasReceiver<Option<R>> + HasSender<Option<R>> + Resource,
+{
+    let state = &mut *state;
+    let inserted_by_leptos = std::mem::take(&mut state.inner.inserted_by_leptos);
+
+    let changed = match &resource {
+        Some(resource) => {
+            (state.bevy_changed(resource.last_changed(), ticks.this_run())
+                || !state.inner.prev_some)
+                && !inserted_by_leptos
+        }
+        None => state.inner.prev_some,
+    };
+
+    state.inner.prev_some = resource.is_some();
+    state.checked = ticks.this_run();
+
+    if changed {
+        let value = resource.as_deref().cloned();
+
+        #[cfg(feature = "record")]
+        recording.record::<D, _>(BridgeDirection::BevyToLeptos, &value);
+
+        outbox.send(sync.tx(), value);
+    }
+}
+
+/// A field of a resource that is synced with a Leptos signal. This is the bridge resource of
+/// [`sync_signal_resource_field`].
+///
+/// `G` and `GM` are the types of the functions that project the resource to the field. Every
+/// closure has its own type so several fields of the same type can be synced at once.
+#[derive(Resource)]
+pub struct ResourceFieldSync<D, G, GM> {
+    sync: D,
+    get: G,
+    get_mut: GM,
+}
+
+impl<D, G, GM> ResourceFieldSync<D, G, GM> {
+    pub fn new(sync: D, get: G, get_mut: GM) -> Self {
+        Self { sync, get, get_mut }
+    }
+
+    /// The synced field of `resource`.
+    #[cfg(feature = "record")]
+    pub(crate) fn field_mut<'a, R, F>(&self, resource: &'a mut R) -> &'a mut F
+    where
+        GM: Fn(&mut R) -> &mut F,
+    {
+        (self.get_mut)(resource)
+    }
+}
+
+impl<D, G, GM, F> HasReceiver<F> for ResourceFieldSync<D, G, GM>
+where
+    D: HasReceiver<F>,
+{
+    fn rx(&self) -> &Receiver<F> {
+        self.sync.rx()
+    }
+}
+
+impl<D, G, GM, F> HasSender<F> for ResourceFieldSync<D, G, GM>
 where
+    D: HasSender<F>,
+{
+    fn tx(&self) -> &Sender<F> {
+        self.sync.tx()
+    }
+}
+
+/// Applies the values written in Leptos to a field of a resource. Values from Leptos only mark
+/// the resource as changed if they actually change the field so other syncs of the resource
+/// don't send it back. The changes of Bevy are sent by [`send_signal_resource_field`].
+///
+/// The state remembers the last value that has been sent to or received from Leptos.
+pub fn apply_signal_resource_field<D, R, F, G, GM>(
+    field_sync: Res<ResourceFieldSync<D, G, GM>>,
+    resource: Option<ResMut<R>>,
+    mut state: ResMut<SyncState<ResourceFieldSync<D, G, GM>, Option<F>>>,
+    #[cfg(feature = "record")] mut recording: BridgeRecording,
+) where
+    R: Resource,
+    F: Clone + PartialEq + Send + Sync + 'static,
+    D: HasReceiver<F> + HasSender<F> + Send + Sync + 'static,
+    G: Fn(&R) -> &F + Send + Sync + 'static,
+    GM: Fn(&mut R) -> &mut F + Send + Sync + 'static,
+{
+    let Some(mut resource) = resource else {
+        return;
+    };
+
+    // a newly attached signal gets the current value
+    if field_sync.is_added() {
+        state.inner = None;
+    }
+
+    for value in field_sync.rx().try_iter() {
+        #[cfg(feature = "record")]
+        recording.record::<ResourceFieldSync<D, G, GM>, _>(BridgeDirection::LeptosToBevy, &value);
+
+        let field = (field_sync.get_mut)(resource.bypass_change_detection());
+        if *field != value {
+            *field = value.clone();
+            resource.set_changed();
+        }
+
+        state.inner = Some(value);
+    }
+}
+
+/// Sends a field of a resource to Leptos when it changed. Only the field is sent.
+pub fn send_signal_resource_field<D, R, F, G, GM>(
+    field_sync: Res<ResourceFieldSync<D, G, GM>>,
+    resource: Option<Res<R>>,
+    mut state: ResMut<SyncState<ResourceFieldSync<D, G, GM>, Option<F>>>,
+    outbox: Res<LeptosOutbox>,
+    #[cfg(feature = "record")] mut recording: BridgeRecording,
+) where
+    R: Resource,
+    F: Clone + PartialEq + Send + Sync + 'static,
+    D: HasReceiver<F> + HasSender<F> + Send + Sync + 'static,
+    G: Fn(&R) -> &F + Send + Sync + 'static,
+    GM: Fn(&mut R) -> &mut F + Send + Sync + 'static,
+{
+    let Some(resource) = resource else {
+        return;
+    };
+
+    if resource.is_changed() || state.inner.is_none() {
+        let value = (field_sync.get)(&resource);
+
+        if state.inner.as_ref() != Some(value) {
+            #[cfg(feature = "record")]
+            recording
+                .record::<ResourceFieldSync<D, G, GM>, _>(BridgeDirection::BevyToLeptos, value);
+
+            outbox.send(field_sync.tx(), value.clone());
+            state.inner = Some(value.clone());
+        }
+    }
+}
+
+/// What the diffed sync of a resource remembers.
+pub struct DiffedSync<R> {
+    /// The last value that Leptos and Bevy agreed on
+    synced: Option<R>,
+    version: u64,
+}
+
+impl<R> Default for DiffedSync<R> {
+    fn default() -> Self {
+        Self {
+            synced: None,
+            version: 0,
+        }
+    }
+}
+
+/// Applies the changes that Leptos sent for a diffed resource. If a patch from Leptos can't be
+/// applied or is based on a version that Bevy has already changed, Leptos is resynced by
+/// [`send_signal_diffed_resource`] which also sends the changes of Bevy.
+pub fn apply_signal_diffed_resource<D, R>(
+    resource: Option<ResMut<R>>,
+    sync: Res<D>,
+    mut state: ResMut<SyncState<D, DiffedSync<R>>>,
+    #[cfg(feature = "record")] mut recording: BridgeRecording,
+) where
+    R: Resource + Diffable,
+    D: HasReceiver<SyncUpdate<R>> + HasSender<SyncUpdate<R>> + Resource,
+{
+    let Some(mut resource) = resource else {
+        return;
+    };
+
+    let state = &mut *state;
+
+    // Bevy might have changed the resource since the initial value from Leptos has been
+    // inserted, e.g. while a run condition was `false`, so Leptos is sent a snapshot first
+    if sync.is_added() {
+        state.inner.synced = None;
+        state.resync = true;
+    }
+
+    let synced = state.inner.synced.get_or_insert_with(|| resource.clone());
+    let version = &mut state.inner.version;
+
+    for update in sync.rx().try_iter() {
+        match update {
+            SyncUpdate::Snapshot {
+                version: snapshot_version,
+                value,
+            } => {
+                *resource = value;
+                catch_up(synced, &resource);
+                *version = snapshot_version;
+                state.resync = false;
+            }
+            SyncUpdate::Patch { base, patch } => {
+                // a patch of an older version has been made while Bevy changed the value so
+                // Bevy decides what the value is
+                if state.resync
+                    || base != *version
+                    || synced.apply_patch(patch.clone()).is_err()
+                    || resource.apply_patch(patch).is_err()
+                {
+                    state.resync = true;
+                    continue;
+                }
+
+                *version += 1;
+            }
+            SyncUpdate::Resync => {
+                state.resync = true;
+                continue;
+            }
+        }
+
+        #[cfg(feature = "record")]
+        recording.record::<D, _>(BridgeDirection::LeptosToBevy, &*resource);
+    }
+}
+
+/// Sends the changes of a diffed resource to Leptos as patches or a snapshot if Leptos has to be
+/// resynced.
+pub fn send_signal_diffed_resource<D, R>(
+    resource: Option<Res<R>>,
+    sync: Res<D>,
+    mut state: ResMut<SyncState<D, DiffedSync<R>>>,
+    outbox: Res<LeptosOutbox>,
+    #[cfg(feature = "record")] mut recording: BridgeRecording,
+) where
+    R: Resource + Diffable,
+    D: HasReceiver<SyncUpdate<R>> + HasSender<SyncUpdate<R>> + Resource,
+{
+    let Some(resource) = resource else {
+        return;
+    };
+
+    let state = &mut *state;
+    let resync = std::mem::take(&mut state.resync);
+    let version = &mut state.inner.version;
+    let synced = state.inner.synced.get_or_insert_with(|| resource.clone());
+
+    if resync {
+        #[cfg(feature = "record")]
+        recording.record::<D, _>(BridgeDirection::BevyToLeptos, &*resource);
+
+        catch_up(synced, &resource);
+        *version += 1;
+        outbox.send(
+            sync.tx(),
+            SyncUpdate::Snapshot {
+                version: *version,
+                value: resource.clone(),
+            },
+        );
+    } else if resource.is_changed() {
+        if let Some(patch) = resource.diff(synced) {
+            #[cfg(feature = "record")]
+            recording.record::<D, _>(BridgeDirection::BevyToLeptos, &*resource);
+
+            outbox.send(
+                sync.tx(),
+                SyncUpdate::Patch {
+                    base: *version,
+                    patch: patch.clone(),
+                },
+            );
+
+            // a patch always fits the value it has been created from
+            if synced.apply_patch(patch).is_err() {
+                *synced = resource.clone();
+            }
+            *version += 1;
+        }
+    }
+}
+
+/// Brings the synced value up to date with `value` by patching it instead of cloning `value`.
+fn catch_up<R: Diffable>(synced: &mut R, value: &R) {
+    if let Some(patch) = value.diff(synced) {
+        if synced.apply_patch(patch).is_err() {
+            *synced = value.clone();
+        }
+    }
+}
+
+/// Sends a resource to Leptos whenever it is added or changed.
+pub fn export_resource<S, R>(
+    resource: Option<Res<R>>,
+    sync: Res<S>,
+    outbox: Res<LeptosOutbox>,
+    #[cfg(feature = "record")] mut recording: BridgeRecording,
+) where
+    R: Resource + Clone,
+    S: HasSender<R> + Resource,
+{
+    let Some(resource) = resource else {
+        return;
+    };
+
+    // a newly attached signal gets the current value
+    if resource.is_changed() || sync.is_added() {
+        #[cfg(feature = "record")]
+        recording.record::<S, _>(BridgeDirection::BevyToLeptos, &*resource);
+
+        outbox.send(sync.tx(), resource.clone());
+    }
+}
+
+/// Takes care of synchronizing a state between Bevy and a Leptos signal.
+///
+/// Values from Leptos are applied through `NextState` so the usual state transition schedules
+/// like `OnEnter` run. Transitions that happen in Bevy are detected with `StateTransitionEvent`s
+/// and sent to Leptos. A transition that Bevy requested through `NextState` but that hasn't been
+/// applied yet counts as a Bevy change as well when resolving a conflict with a Leptos write.
+#[cfg(feature = "bevy_state")]
+#[allow(clippy::too_many_arguments)]
+pub fn sync_signal_state<D, S>(
+    state: Res<State<S>>,
+    mut next_state: ResMut<NextState<S>>,
+    mut transitions: MessageReader<StateTransitionEvent<S>>,
+    sync: Res<D>,
+    options: Option<Res<SyncOptions<S>>>,
+    frame_end: Option<Res<LeptosFrameEndTick>>,
+    time: Option<Res<Time<Real>>>,
+    ticks: SystemChangeTick,
+    // the last state that has been set from Leptos which must not be sent back
+    mut from_leptos: Local<Option<S>>,
+    mut throttle: Local<SendThrottle>,
+    outbox: Res<LeptosOutbox>,
+    #[cfg(feature = "record")] mut recording: BridgeRecording,
+) where
     S: bevy::state::state::FreelyMutableState + Clone,
     D: HasReceiver<S> + HasSender<S> + Resource,
 {
-    if state.is_changed() && !state.is_added() {
-        sync.tx().send(state.clone()).unwrap();
+    let mut bevy_changed = false;
+
+    for transition in transitions.read() {
+        if transition.entered.is_some() && transition.entered != *from_leptos {
+            bevy_changed = true;
+        }
     }
+    *from_leptos = None;
+
+    let bevy_pending = match &*next_state {
+        NextState::Pending(pending) | NextState::PendingIfNeq(pending) => Some(pending.clone()),
+        NextState::Unchanged => None,
+    };
 
+    let mut leptos_value = None;
     for event in sync.rx().try_iter() {
-        *state = State::new(event);
+        #[cfg(feature = "record")]
+        recording.record::<D, _>(BridgeDirection::LeptosToBevy, &event);
+
+        leptos_value = Some(event);
+    }
+
+    let send = match leptos_value {
+        None => bevy_changed,
+        Some(value) => {
+            let conflict = (bevy_changed || bevy_pending.is_some()).then(|| {
+                frame_end.is_some_and(|frame_end| {
+                    (bevy_changed
+                        && frame_end.changed_this_frame(state.last_changed(), ticks.this_run()))
+                        || (bevy_pending.is_some()
+                            && frame_end
+                                .changed_this_frame(next_state.last_changed(), ticks.this_run()))
+                })
+            });
+
+            match resolve_leptos_value(options.as_deref(), state.get(), value, conflict) {
+                Resolved::Apply(value, send_back) => {
+                    if send_back {
+                        #[cfg(feature = "record")]
+                        recording.record::<D, _>(BridgeDirection::BevyToLeptos, &value);
+
+                        outbox.send(sync.tx(), value.clone());
+                    }
+
+                    *from_leptos = Some(value.clone());
+                    next_state.set(value);
+                    false
+                }
+                // the pending transition is applied after this system so Leptos gets its target
+                Resolved::Keep => match bevy_pending {
+                    Some(pending) => {
+                        #[cfg(feature = "record")]
+                        recording.record::<D, _>(BridgeDirection::BevyToLeptos, &pending);
+
+                        outbox.send(sync.tx(), pending);
+                        false
+                    }
+                    None => true,
+                },
+            }
+        }
+    };
+
+    if send {
+        throttle.mark();
+    }
+
+    let min_interval = options.and_then(|options| options.min_send_interval());
+
+    if throttle.ready(min_interval, time.map(|time| time.elapsed())) {
+        #[cfg(feature = "record")]
+        recording.record::<D, _>(BridgeDirection::BevyToLeptos, state.get());
+
+        outbox.send(sync.tx(), state.get().clone());
     }
 }
 
-/// Synchronizes a Bevy query's `.get_single_mut()` with a Leptos signal.
-pub fn sync_query<D, F>(
+/// Sends a state of any kind to Leptos whenever it changes. Sends `None` when it is removed.
+#[cfg(feature = "bevy_state")]
+pub fn export_state<T, S>(
+    state: Option<Res<State<S>>>,
+    sync: Res<T>,
+    mut prev_some: Local<bool>,
+    outbox: Res<LeptosOutbox>,
+    #[cfg(feature = "record")] mut recording: BridgeRecording,
+) where
+    S: States,
+    T: HasSender<Option<S>> + Resource,
+{
+    let changed = match &state {
+        Some(state) => state.is_changed() || !*prev_some || sync.is_added(),
+        None => *prev_some,
+    };
+
+    *prev_some = state.is_some();
+
+    if changed {
+        let value = state.map(|state| state.get().clone());
+
+        #[cfg(feature = "record")]
+        recording.record::<T, _>(BridgeDirection::BevyToLeptos, &value);
+
+        outbox.send(sync.tx(), value);
+    }
+}
+
+/// Applies the values written in Leptos to the single entity of a query. The changes of Bevy are
+/// sent by [`send_signal_query`].
+///
+/// The state remembers if the query matched an entity when the changes have been checked last.
+pub fn apply_signal_query<D, F>(
     duplex: Res<BevyMessageDuplex<Option<D>>>,
     mut query: Query<<D as QueryDataOwned>::Qdata, F>,
-    mut prev_some: Local<bool>,
+    mut state: ResMut<SyncState<BevyMessageDuplex<Option<D>>, bool>>,
+    ticks: SystemChangeTick,
+    #[cfg(feature = "record")] mut recording: BridgeRecording,
 ) where
     for<'a> D: QueryDataOwned<'a> + Send + Sync + 'static,
     F: QueryFilter,
 {
+    let this_run = ticks.this_run();
     let mut item = query.single_mut().ok();
 
-    let changed = if let Some(item) = &item {
-        !*prev_some || D::is_changed(item)
-    } else {
-        *prev_some
+    let bevy_changed = item
+        .as_ref()
+        .is_some_and(|item| state.bevy_changed(D::last_changed(item, this_run), this_run));
+
+    let mut leptos_value = None;
+    for message in duplex.rx().try_iter() {
+        #[cfg(feature = "record")]
+        recording
+            .record::<BevyMessageDuplex<Option<D>>, _>(BridgeDirection::LeptosToBevy, &message);
+
+        leptos_value = Some(message);
+    }
+
+    let Some(value) = leptos_value else {
+        if bevy_changed {
+            state.throttle.mark();
+        }
+        return;
+    };
+
+    // Leptos hasn't seen the changes made while the signal wasn't observed, so a value written
+    // in Leptos is based on outdated data. It is dropped and Leptos is resynced instead.
+    if !duplex.is_observed() && (state.throttle.is_pending() || bevy_changed) {
+        state.resync = true;
+        return;
+    }
+
+    if let (Some(value), Some(item)) = (value, &mut item) {
+        value.set_query_data(item);
+        state.applied = Some(this_run);
+    }
+}
+
+/// Sends the data of the single entity of a query to Leptos when it changed. Sends `None` when
+/// the query doesn't match exactly one entity anymore.
+#[allow(clippy::too_many_arguments)]
+pub fn send_signal_query<D, F>(
+    duplex: Res<BevyMessageDuplex<Option<D>>>,
+    mut query: Query<<D as QueryDataOwned>::Qdata, F>,
+    mut state: ResMut<SyncState<BevyMessageDuplex<Option<D>>, bool>>,
+    options: Option<Res<SyncOptions<Option<D>>>>,
+    time: Option<Res<Time<Real>>>,
+    ticks: SystemChangeTick,
+    outbox: Res<LeptosOutbox>,
+    #[cfg(feature = "record")] mut recording: BridgeRecording,
+) where
+    for<'a> D: QueryDataOwned<'a> + Send + Sync + 'static,
+    F: QueryFilter,
+{
+    let this_run = ticks.this_run();
+    let state = &mut *state;
+    let item = query.single_mut().ok();
+
+    let changed = match &item {
+        Some(item) => {
+            !state.inner
+                || state.bevy_changed(D::last_changed(item, this_run), this_run)
+                || duplex.is_added()
+        }
+        None => state.inner,
     };
 
-    *prev_some = item.is_some();
+    state.inner = item.is_some();
+    state.checked = this_run;
 
     if changed {
+        state.throttle.mark();
+    }
+
+    let resync = std::mem::take(&mut state.resync);
+
+    // query data that hasn't been sent because the signal wasn't observed is sent once it is
+    if !resync && !duplex.is_observed() {
+        return;
+    }
+
+    let min_interval = options.and_then(|options| options.min_send_interval());
+    let now = time.map(|time| time.elapsed());
+
+    if resync {
+        state.throttle.sent(now);
+    }
+
+    if resync || state.throttle.ready(min_interval, now) {
         let item = item.map(|item| D::from_query_data(&item));
-        duplex.tx().send(item).unwrap();
-    } else {
-        for message in duplex.rx().try_iter() {
-            if let (Some(message), Some(item)) = (message, &mut item) {
-                message.set_query_data(item);
-            }
-        }
+
+        #[cfg(feature = "record")]
+        recording.record::<BevyMessageDuplex<Option<D>>, _>(BridgeDirection::BevyToLeptos, &item);
+
+        outbox.send(duplex.tx(), item);
     }
 }
@@ -0,0 +1,185 @@
+//! Utilities to test the glue between your Bevy app and your Leptos app natively without a
+//! browser or a GPU.
+//!
+//! ## Example
+//!
+//! ```
+//! # use bevy::prelude::*;
+//! # use leptos::prelude::{GetUntracked, Set};
+//! # use leptos_bevy_canvas::prelude::*;
+//! # use leptos_bevy_canvas::testing::BevyTestApp;
+//! #
+//! #[derive(Resource, Clone, Debug, PartialEq)]
+//! struct Score(u32);
+//!
+//! #[derive(Message)]
+//! struct Scored;
+//!
+//! fn add_score(mut messages: MessageReader<Scored>, mut score: ResMut<Score>) {
+//!     for _ in messages.read() {
+//!         score.0 += 1;
+//!     }
+//! }
+//!
+//! let mut test_app = BevyTestApp::new();
+//!
+//! let ((score, bevy_score), (scored, bevy_scored)) =
+//!     test_app.leptos(|| (signal_synced(Score(0)), message_l2b::<Scored>()));
+//!
+//! test_app
+//!     .app_mut()
+//!     .sync_leptos_signal_with_resource(bevy_score)
+//!     .import_message_from_leptos(bevy_scored)
+//!     .add_systems(Update, add_score);
+//!
+//! // run the first frame in which the synced resource is added
+//! test_app.update();
+//!
+//! scored.send(Scored).unwrap();
+//! test_app.update();
+//!
+//! assert_eq!(test_app.resource::<Score>(), &Score(1));
+//! assert_eq!(score.get_untracked(), Score(1));
+//!
+//! score.set(Score(10));
+//! test_app.update();
+//!
+//! assert_eq!(test_app.resource::<Score>(), &Score(10));
+//! ```
+
+use crate::plugin::LeptosBevyCanvasPlugin;
+use crate::utils::run_frame;
+use bevy::app::PluginsState;
+use bevy::ecs::query::QueryFilter;
+use bevy::prelude::*;
+use leptos::prelude::Owner;
+
+/// A headless Bevy app together with a Leptos reactive owner that lets you step frames
+/// deterministically.
+///
+/// Every call to [`BevyTestApp::update`] runs one Leptos animation frame (which sends all the
+/// writes and messages from Leptos to Bevy), one Bevy frame and another Leptos animation
+/// frame (which applies everything that Bevy sent to the Leptos signals).
+pub struct BevyTestApp {
+    app: App,
+    owner: Owner,
+}
+
+impl Default for BevyTestApp {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BevyTestApp {
+    /// Creates a new test app with `MinimalPlugins` (and `StatesPlugin` if the `bevy_state`
+    /// feature is enabled).
+    pub fn new() -> Self {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+
+        #[cfg(feature = "bevy_state")]
+        app.add_plugins(bevy::state::app::StatesPlugin);
+
+        Self::from_app(app)
+    }
+
+    /// Creates a new test app from an existing Bevy app.
+    pub fn from_app(mut app: App) -> Self {
+        app.add_plugins(LeptosBevyCanvasPlugin);
+
+        Self {
+            app,
+            owner: Owner::new(),
+        }
+    }
+
+    /// Runs `f` inside the Leptos reactive owner of this test app. Use this to create the
+    /// Leptos side of the bridges like signals and message senders.
+    pub fn leptos<T>(&self, f: impl FnOnce() -> T) -> T {
+        self.owner.with(f)
+    }
+
+    /// The Bevy app.
+    pub fn app(&self) -> &App {
+        &self.app
+    }
+
+    /// The Bevy app.
+    pub fn app_mut(&mut self) -> &mut App {
+        &mut self.app
+    }
+
+    /// The Bevy world.
+    pub fn world(&self) -> &World {
+        self.app.world()
+    }
+
+    /// The Bevy world.
+    pub fn world_mut(&mut self) -> &mut World {
+        self.app.world_mut()
+    }
+
+    /// Runs one Leptos animation frame, one Bevy frame and another Leptos animation frame.
+    pub fn update(&mut self) -> &mut Self {
+        if self.app.plugins_state() == PluginsState::Ready {
+            self.app.finish();
+            self.app.cleanup();
+        }
+
+        self.run_leptos_frame();
+        self.app.update();
+        self.run_leptos_frame();
+
+        self
+    }
+
+    /// Calls [`BevyTestApp::update`] `n` times.
+    pub fn update_n(&mut self, n: usize) -> &mut Self {
+        for _ in 0..n {
+            self.update();
+        }
+
+        self
+    }
+
+    /// Runs only a Leptos animation frame without updating the Bevy app. This sends pending
+    /// writes to Bevy and applies pending updates from Bevy to the Leptos signals.
+    pub fn run_leptos_frame(&mut self) -> &mut Self {
+        self.owner.with(run_frame);
+
+        self
+    }
+
+    /// Returns the Bevy resource `R`. Panics if it doesn't exist.
+    pub fn resource<R: Resource>(&self) -> &R {
+        self.app.world().resource::<R>()
+    }
+
+    /// Returns the current value of the Bevy state `S`. Panics if it doesn't exist.
+    #[cfg(feature = "bevy_state")]
+    pub fn state<S: bevy::state::state::States>(&self) -> &S {
+        self.app
+            .world()
+            .resource::<bevy::state::state::State<S>>()
+            .get()
+    }
+
+    /// Returns the component `C` of the single entity that has it. Panics if there is not
+    /// exactly one such entity.
+    pub fn single<C: Component>(&mut self) -> &C {
+        self.single_filtered::<C, ()>()
+    }
+
+    /// Returns the component `C` of the single entity that has it and matches the query filter
+    /// `F`. Panics if there is not exactly one such entity.
+    pub fn single_filtered<C: Component, F: QueryFilter>(&mut self) -> &C {
+        let world = self.app.world_mut();
+        let entity = world
+            .query_filtered::<Entity, (With<C>, F)>()
+            .single(world)
+            .expect("expected exactly one matching entity");
+
+        world.get::<C>(entity).expect("entity has the component")
+    }
+}
@@ -1,4 +1,4 @@
-use crossbeam_channel::{Receiver, Sender};
+use crate::channel::{Receiver, Sender};
 
 pub trait HasReceiver<T> {
     fn rx(&self) -> &Receiver<T>;
@@ -6,4 +6,10 @@ pub trait HasReceiver<T> {
 
 pub trait HasSender<T> {
     fn tx(&self) -> &Sender<T>;
+
+    /// Returns `false` if nothing in Leptos observes the synced value right now so sending it
+    /// can be skipped.
+    fn is_observed(&self) -> bool {
+        true
+    }
 }
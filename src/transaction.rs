@@ -0,0 +1,320 @@
+use crate::channel::Sender;
+use crate::utils::{on_next_frame, PendingSend};
+use crossbeam_channel::TrySendError;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+#[derive(Default)]
+struct Transaction {
+    /// Nested calls of [`bevy_transaction`]
+    depth: u32,
+    /// Sends of synced signals that would have happened on the next frame by the key of the
+    /// signal. Every signal is sent only once per transaction.
+    on_commit: Vec<(usize, Box<dyn FnOnce()>)>,
+    sends: Vec<Box<dyn PendingSend>>,
+}
+
+/// How many values of committed transactions may wait for space in their channels. Sends
+/// beyond that fail like sends to a full channel.
+const MAX_COMMITTED: usize = 1000;
+
+thread_local! {
+    static TRANSACTION: RefCell<Option<Transaction>> = const { RefCell::new(None) };
+
+    /// Committed transactions that didn't fit into their channels yet in order. Sends that happen
+    /// after a transaction has been committed are queued behind it if they use one of its
+    /// channels so they never overtake it.
+    static COMMITTED: RefCell<VecDeque<Vec<Box<dyn PendingSend>>>> =
+        const { RefCell::new(VecDeque::new()) };
+
+    /// `true` while the delivery of [`COMMITTED`] is retried on the next frame
+    static RETRYING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Groups writes to synced signals and messages sent to Bevy so that Bevy applies all of them
+/// in the same frame.
+///
+/// Everything that is written or sent inside `f` is held back until `f` returns. Then it is
+/// committed as a whole and sent to Bevy at once. Message imports, state syncs and (by default)
+/// Leptos values of resource and query syncs are all applied in `PreUpdate` so your own systems
+/// in `Update` see the values of the transaction together.
+///
+/// If one of the channels doesn't have enough space for its part of the transaction nothing is
+/// sent and the whole transaction is retried on the next frame. Values that are written or sent
+/// over the same channels after the transaction are queued behind it until it has been sent so
+/// they never overtake it. Other channels aren't held up. A transaction that sends more values
+/// over a channel than the channel can hold (50 messages) is split: the channel is filled and the
+/// rest follows in later frames.
+///
+/// At most 1000 values wait for space this way. Transactions committed beyond that are
+/// discarded with a warning and sends fail like sends to a full channel.
+///
+/// In the browser Leptos and Bevy run on the same thread so a Bevy frame always sees either all
+/// or nothing of a transaction. A Bevy app that runs on a
+/// [`BevyThread`](crate::prelude::BevyThread) can receive a transaction while it is in the middle
+/// of a frame though.
+///
+/// Transactions can be nested in which case the outermost one commits. If `f` panics nothing
+/// is committed.
+///
+/// ```
+/// # use bevy::prelude::*;
+/// # use leptos::prelude::*;
+/// # use leptos_bevy_canvas::prelude::*;
+/// #
+/// #[derive(Resource, Clone, Default)]
+/// struct Tool(u32);
+///
+/// #[derive(Message, Clone)]
+/// struct Clicked;
+///
+/// # let owner = Owner::new();
+/// # owner.with(|| {
+/// let (tool, bevy_tool) = signal_synced(Tool::default());
+/// let (clicked, bevy_clicked) = message_l2b::<Clicked>();
+///
+/// // Bevy sees the new tool in the same frame as the click
+/// bevy_transaction(|| {
+///     tool.set(Tool(1));
+///     clicked.send(Clicked).unwrap();
+/// });
+/// # });
+/// ```
+pub fn bevy_transaction<T>(f: impl FnOnce() -> T) -> T {
+    TRANSACTION.with_borrow_mut(|transaction| {
+        transaction.get_or_insert_with(Transaction::default).depth += 1;
+    });
+
+    let _guard = TransactionGuard;
+
+    f()
+}
+
+/// Ends the transaction when [`bevy_transaction`] returns or panics.
+struct TransactionGuard;
+
+impl Drop for TransactionGuard {
+    fn drop(&mut self) {
+        let on_commit = TRANSACTION.with_borrow_mut(|transaction| {
+            let Some(current) = transaction else {
+                return Vec::new();
+            };
+
+            current.depth -= 1;
+
+            if current.depth > 0 {
+                return Vec::new();
+            }
+
+            if std::thread::panicking() {
+                *transaction = None;
+                return Vec::new();
+            }
+
+            std::mem::take(&mut current.on_commit)
+        });
+
+        // the signals send their values now which are added to the transaction
+        for (_, callback) in on_commit {
+            callback();
+        }
+
+        if let Some(transaction) = TRANSACTION.take() {
+            if transaction.depth == 0 {
+                commit(transaction.sends);
+            } else {
+                TRANSACTION.set(Some(transaction));
+            }
+        }
+    }
+}
+
+/// Sends `value` to Bevy without blocking. Inside a [`bevy_transaction`] the value is added to
+/// the transaction.
+///
+/// Fails if the channel is full, e.g. because Bevy doesn't read it while a run condition is
+/// `false`. Leptos must never block here because it runs on the same thread as Bevy in the
+/// browser.
+pub(crate) fn send_to_bevy<T>(tx: &Sender<T>, value: T) -> Result<(), TrySendError<T>>
+where
+    T: Send + 'static,
+{
+    TRANSACTION.with_borrow_mut(|transaction| match transaction {
+        Some(transaction) => {
+            transaction.sends.push(Box::new((tx.clone(), Some(value))));
+            Ok(())
+        }
+        None => COMMITTED.with_borrow_mut(|committed| {
+            let waiting = committed
+                .iter()
+                .flatten()
+                .any(|pending| pending.channel_id() == tx.id());
+
+            if !waiting {
+                tx.try_send(value)
+            } else if queued(committed) >= MAX_COMMITTED {
+                Err(TrySendError::Full(value))
+            } else {
+                // a committed transaction waits for space in this channel
+                committed.push_back(vec![Box::new((tx.clone(), Some(value)))]);
+                Ok(())
+            }
+        }),
+    })
+}
+
+/// Returns `true` inside a [`bevy_transaction`].
+pub(crate) fn in_transaction() -> bool {
+    TRANSACTION.with_borrow(Option::is_some)
+}
+
+/// Calls `callback` on the next frame or when the current [`bevy_transaction`] is committed.
+///
+/// `key` identifies the signal that is sent by the callback. Inside a transaction only the first
+/// callback of every signal is kept.
+pub(crate) fn on_next_frame_or_commit(key: usize, callback: impl FnOnce() + 'static) {
+    let callback = TRANSACTION.with_borrow_mut(|transaction| match transaction {
+        Some(transaction) => {
+            if !transaction.on_commit.iter().any(|(other, _)| *other == key) {
+                transaction.on_commit.push((key, Box::new(callback)));
+            }
+            None
+        }
+        None => Some(callback),
+    });
+
+    if let Some(callback) = callback {
+        on_next_frame(callback);
+    }
+}
+
+/// Queues the sends of a committed transaction and delivers them.
+fn commit(sends: Vec<Box<dyn PendingSend>>) {
+    if sends.is_empty() {
+        return;
+    }
+
+    let discarded = COMMITTED.with_borrow_mut(|committed| {
+        let queued = queued(committed);
+
+        if queued > 0 && queued + sends.len() > MAX_COMMITTED {
+            return true;
+        }
+
+        for (channel, count) in counts(&sends) {
+            let capacity = sends
+                .iter()
+                .find(|pending| pending.channel_id() == channel)
+                .and_then(|pending| pending.capacity());
+
+            if let Some(capacity) = capacity.filter(|capacity| count > *capacity) {
+                bevy::log::warn!(
+                    "A transaction sends {count} values over a channel that only holds \
+                     {capacity}. It is split over several frames."
+                );
+            }
+        }
+
+        committed.push_back(sends);
+        false
+    });
+
+    if discarded {
+        bevy::log::warn!(
+            "Too many transactions wait for Bevy to read its channels. A transaction is discarded."
+        );
+    }
+
+    deliver_committed();
+}
+
+/// Sends the committed transactions. A transaction is only sent if all of its values fit into
+/// their channels. Otherwise it is retried on the next frame and later transactions that use one
+/// of its channels wait for it.
+fn deliver_committed() {
+    let remaining = COMMITTED.with_borrow_mut(|committed| {
+        // the channels of the transactions that are still waiting
+        let mut waiting = HashSet::new();
+
+        for sends in committed.iter_mut() {
+            let channels = counts(sends).into_keys().collect::<HashSet<_>>();
+
+            if channels.is_disjoint(&waiting) && fits(sends) {
+                send_in_order(sends);
+            }
+
+            // a transaction that is larger than a channel is sent in parts
+            if !sends.is_empty() {
+                waiting.extend(channels);
+            }
+        }
+
+        committed.retain(|sends| !sends.is_empty());
+
+        queued(committed)
+    });
+
+    if remaining > 0 && !RETRYING.replace(true) {
+        bevy::log::warn!(
+            "A channel to Bevy is full. {remaining} values of transactions are sent in a later \
+             frame."
+        );
+
+        on_next_frame(|| {
+            RETRYING.set(false);
+            deliver_committed();
+        });
+    }
+}
+
+/// Sends as many values as fit and removes them. Once a value doesn't fit the later values of
+/// the same channel are kept as well so they stay in order.
+fn send_in_order(sends: &mut Vec<Box<dyn PendingSend>>) {
+    let mut full = HashSet::new();
+
+    sends.retain_mut(|pending| {
+        if full.contains(&pending.channel_id()) {
+            return true;
+        }
+
+        let sent = pending.try_send();
+        if !sent {
+            full.insert(pending.channel_id());
+        }
+
+        !sent
+    });
+}
+
+/// Returns `true` if every channel has enough space for all the values that are sent over it or
+/// is empty if there are more values than it can hold.
+fn fits(sends: &[Box<dyn PendingSend>]) -> bool {
+    let counts = counts(sends);
+
+    sends.iter().all(|pending| {
+        let count = counts[&pending.channel_id()];
+        let needed = pending
+            .capacity()
+            .map_or(count, |capacity| count.min(capacity));
+
+        pending
+            .remaining_capacity()
+            .is_none_or(|remaining| remaining >= needed)
+    })
+}
+
+/// The number of values that are sent over each channel.
+fn counts(sends: &[Box<dyn PendingSend>]) -> HashMap<usize, usize> {
+    let mut counts = HashMap::new();
+
+    for pending in sends {
+        *counts.entry(pending.channel_id()).or_default() += 1;
+    }
+
+    counts
+}
+
+/// The number of values that wait to be sent.
+fn queued(committed: &VecDeque<Vec<Box<dyn PendingSend>>>) -> usize {
+    committed.iter().map(Vec::len).sum()
+}
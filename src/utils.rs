@@ -1,18 +1,32 @@
-use crossbeam_channel::Receiver;
+use crate::channel::{Receiver, Sender};
+use crossbeam_channel::TrySendError;
 use leptos::prelude::*;
-use leptos_use::use_raf_fn;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
 
 pub(crate) fn init_rw_signal_from_receiver<M>(rx: &Receiver<M>) -> RwSignal<Option<M>>
+where
+    M: Send + Sync + 'static,
+{
+    init_message_signal(rx, MessageListeners::default())
+}
+
+/// Same as [`init_rw_signal_from_receiver`] but also calls the `listeners` with every message.
+/// The signal only keeps the last message of a frame while the listeners get all of them.
+pub(crate) fn init_message_signal<M>(
+    rx: &Receiver<M>,
+    listeners: MessageListeners<M>,
+) -> RwSignal<Option<M>>
 where
     M: Send + Sync + 'static,
 {
     let signal = RwSignal::new(None);
 
-    use_raf_fn({
+    use_frame_fn({
         let rx = rx.clone();
 
-        move |_| {
+        move || {
             for event in rx.try_iter() {
+                listeners.notify(&event);
                 signal.set(Some(event));
             }
         }
@@ -20,3 +34,323 @@ where
 
     signal
 }
+
+type MessageListener<M> = Box<dyn FnMut(&M) + Send + Sync>;
+
+struct MessageListenersInner<M> {
+    next_id: u64,
+    listeners: Vec<(u64, MessageListener<M>)>,
+}
+
+/// The callbacks that are called with every message that is received from Bevy.
+pub(crate) struct MessageListeners<M>(Arc<Mutex<MessageListenersInner<M>>>);
+
+impl<M> Default for MessageListeners<M> {
+    fn default() -> Self {
+        Self(Arc::new(Mutex::new(MessageListenersInner {
+            next_id: 0,
+            listeners: Vec::new(),
+        })))
+    }
+}
+
+impl<M> Clone for MessageListeners<M> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<M: 'static> MessageListeners<M> {
+    /// Adds `listener` until the current reactive owner is cleaned up.
+    pub(crate) fn add(&self, listener: impl FnMut(&M) + Send + Sync + 'static) {
+        let id = {
+            let mut inner = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+            let id = inner.next_id;
+            inner.next_id += 1;
+            inner.listeners.push((id, Box::new(listener)));
+            id
+        };
+
+        let listeners = self.clone();
+        on_cleanup(move || {
+            listeners
+                .0
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .listeners
+                .retain(|(other_id, _)| *other_id != id);
+        });
+    }
+
+    fn notify(&self, message: &M) {
+        let mut inner = self.0.lock().unwrap_or_else(PoisonError::into_inner);
+
+        for (_, listener) in &mut inner.listeners {
+            listener(message);
+        }
+    }
+}
+
+/// Closes the channel when the current reactive owner is cleaned up so that Bevy stops syncing
+/// with it and removes the resource of the bridge.
+pub(crate) fn close_on_cleanup<T: Send + 'static>(tx: &Sender<T>) {
+    let tx = tx.clone();
+
+    on_cleanup(move || tx.close());
+}
+
+/// Makes sure that Leptos never applies the updates of a Bevy frame while Bevy is still sending
+/// them. Bevy holds it while flushing the [`LeptosOutbox`](crate::systems::LeptosOutbox) and
+/// Leptos while running the frame callbacks that apply the updates.
+///
+/// Every Leptos thread has its own lock. The channels created on that thread carry it to the
+/// Bevy apps they are passed to, so apps that talk to different Leptos threads (e.g. tests that
+/// run in parallel) never wait for each other.
+#[derive(Clone, Default)]
+pub(crate) struct BridgeLock(Arc<Mutex<()>>);
+
+thread_local! {
+    static BRIDGE_LOCK: BridgeLock = BridgeLock::default();
+}
+
+impl BridgeLock {
+    /// The lock of the current thread.
+    pub fn current() -> Self {
+        BRIDGE_LOCK.with(Clone::clone)
+    }
+
+    pub fn lock(&self) -> MutexGuard<'_, ()> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Identifies the lock so that several locks are always locked in the same order.
+    pub fn id(&self) -> usize {
+        Arc::as_ptr(&self.0) as usize
+    }
+}
+
+/// A value that is queued to be sent over the bridge later together with its channel.
+pub(crate) trait PendingSend: Send {
+    /// Sends the value if the channel isn't full. Returns `false` if it is. A value sent to a
+    /// closed or disconnected channel is discarded.
+    fn try_send(&mut self) -> bool;
+
+    /// Identifies the channel so that values sent over the same channel keep their order.
+    fn channel_id(&self) -> usize;
+
+    /// How many more values fit into the channel right now. `None` if there is no limit.
+    fn remaining_capacity(&self) -> Option<usize>;
+
+    /// How many values fit into the channel at most. `None` if there is no limit.
+    fn capacity(&self) -> Option<usize>;
+
+    /// The lock of the Leptos thread that receives the value.
+    fn bridge_lock(&self) -> &BridgeLock;
+}
+
+impl<T: Send> PendingSend for (Sender<T>, Option<T>) {
+    fn try_send(&mut self) -> bool {
+        let Some(value) = self.1.take() else {
+            return true;
+        };
+
+        match self.0.try_send(value) {
+            Err(TrySendError::Full(value)) => {
+                self.1 = Some(value);
+                false
+            }
+            Ok(()) | Err(TrySendError::Disconnected(_)) => true,
+        }
+    }
+
+    fn channel_id(&self) -> usize {
+        self.0.id()
+    }
+
+    fn remaining_capacity(&self) -> Option<usize> {
+        self.0.remaining_capacity()
+    }
+
+    fn capacity(&self) -> Option<usize> {
+        self.0.capacity()
+    }
+
+    fn bridge_lock(&self) -> &BridgeLock {
+        self.0.bridge_lock()
+    }
+}
+
+/// Calls `callback` on every animation frame until the current reactive owner is cleaned up.
+///
+/// All the callbacks are called together in one animation frame callback so that the updates
+/// of one Bevy frame are applied at once. In the browser the frames are driven by
+/// `requestAnimationFrame`. Natively the callbacks are called by [`run_frame`]. On the server
+/// (`ssr` feature) this does nothing.
+pub(crate) fn use_frame_fn(callback: impl FnMut() + 'static) {
+    #[cfg(feature = "ssr")]
+    let _ = callback;
+
+    #[cfg(not(feature = "ssr"))]
+    frame::use_frame_fn(callback);
+}
+
+/// Calls `callback` once on the next animation frame before the callbacks registered with
+/// [`use_frame_fn`].
+///
+/// In the browser the frames are driven by `requestAnimationFrame`. Natively the callback is
+/// called by [`run_frame`]. On the server (`ssr` feature) this does nothing.
+pub(crate) fn on_next_frame(callback: impl FnOnce() + 'static) {
+    #[cfg(feature = "ssr")]
+    let _ = callback;
+
+    #[cfg(not(feature = "ssr"))]
+    frame::on_next_frame(callback);
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub use frame::run_frame;
+
+#[cfg_attr(feature = "ssr", allow(dead_code))]
+mod frame {
+    use leptos::prelude::on_cleanup;
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+
+    #[derive(Default)]
+    struct FrameCallbacks {
+        next_id: u64,
+        once: Vec<Box<dyn FnOnce()>>,
+        every: Vec<(u64, Box<dyn FnMut()>)>,
+        removed: HashSet<u64>,
+        /// `true` while the `requestAnimationFrame` loop is running
+        #[cfg(target_arch = "wasm32")]
+        running: bool,
+    }
+
+    impl FrameCallbacks {
+        #[cfg(target_arch = "wasm32")]
+        fn start(&mut self) {
+            if !self.running {
+                self.running = true;
+                leptos::prelude::request_animation_frame(frame_loop);
+            }
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        fn start(&mut self) {}
+    }
+
+    thread_local! {
+        static FRAME_CALLBACKS: RefCell<FrameCallbacks> = RefCell::default();
+    }
+
+    /// Runs the frames as long as there are callbacks.
+    #[cfg(target_arch = "wasm32")]
+    fn frame_loop() {
+        run_frame();
+
+        let running = FRAME_CALLBACKS.with_borrow_mut(|callbacks| {
+            callbacks.running = !callbacks.once.is_empty() || !callbacks.every.is_empty();
+            callbacks.running
+        });
+
+        if running {
+            leptos::prelude::request_animation_frame(frame_loop);
+        }
+    }
+
+    pub(crate) fn use_frame_fn(callback: impl FnMut() + 'static) {
+        let id = FRAME_CALLBACKS.with_borrow_mut(|callbacks| {
+            let id = callbacks.next_id;
+            callbacks.next_id += 1;
+            callbacks.every.push((id, Box::new(callback)));
+            callbacks.start();
+            id
+        });
+
+        on_cleanup(move || {
+            FRAME_CALLBACKS.with_borrow_mut(|callbacks| {
+                let len = callbacks.every.len();
+                callbacks.every.retain(|(other_id, _)| *other_id != id);
+
+                // the callback is currently being run by `run_frame` so remove it afterwards
+                if callbacks.every.len() == len {
+                    callbacks.removed.insert(id);
+                }
+            });
+        });
+    }
+
+    pub(crate) fn on_next_frame(callback: impl FnOnce() + 'static) {
+        FRAME_CALLBACKS.with_borrow_mut(|callbacks| {
+            callbacks.once.push(Box::new(callback));
+            callbacks.start();
+        });
+    }
+
+    /// Runs one Leptos frame of the bridge on the current thread: sends the writes and messages
+    /// that are queued for Bevy and applies the updates received from Bevy to the signals.
+    ///
+    /// In the browser this is driven by `requestAnimationFrame` and you never have to call it.
+    /// Natively, e.g. together with [`BevyThread`](crate::prelude::BevyThread), there is no
+    /// animation frame so you have to call it regularly on the thread that owns the Leptos
+    /// signals, usually from the tick or redraw callback of your UI event loop. Nothing crosses
+    /// the bridge from Leptos to Bevy or back until it is called.
+    ///
+    /// The callbacks that apply the updates from Bevy run together while the bridge is locked so
+    /// they always see complete Bevy frames.
+    ///
+    /// ```
+    /// # use bevy::prelude::*;
+    /// # use leptos::prelude::*;
+    /// # use leptos_bevy_canvas::prelude::*;
+    /// #
+    /// #[derive(Resource, Clone, Default)]
+    /// struct Score(u32);
+    ///
+    /// # let owner = Owner::new();
+    /// # owner.with(|| {
+    /// let (score, bevy_score) = signal_synced(Score::default());
+    ///
+    /// let bevy_thread = BevyThread::spawn(move || {
+    ///     let mut app = App::new();
+    ///     app.add_plugins(MinimalPlugins)
+    ///         .sync_leptos_signal_with_resource(bevy_score);
+    ///     app
+    /// });
+    ///
+    /// score.set(Score(1));
+    ///
+    /// // in the tick callback of the UI event loop
+    /// leptos_bevy_canvas::run_frame();
+    /// # });
+    /// ```
+    pub fn run_frame() {
+        let (once, mut every) = FRAME_CALLBACKS.with_borrow_mut(|callbacks| {
+            (
+                std::mem::take(&mut callbacks.once),
+                std::mem::take(&mut callbacks.every),
+            )
+        });
+
+        for callback in once {
+            callback();
+        }
+
+        {
+            let bridge = super::BridgeLock::current();
+            let _bridge = bridge.lock();
+
+            for (_, callback) in &mut every {
+                callback();
+            }
+        }
+
+        FRAME_CALLBACKS.with_borrow_mut(|callbacks| {
+            let removed = std::mem::take(&mut callbacks.removed);
+            every.retain(|(id, _)| !removed.contains(id));
+            every.append(&mut callbacks.every);
+            callbacks.every = every;
+        });
+    }
+}
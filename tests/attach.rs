@@ -0,0 +1,59 @@
+#![cfg(feature = "testing")]
+
+use bevy::prelude::*;
+use leptos::prelude::*;
+use leptos_bevy_canvas::prelude::*;
+use leptos_bevy_canvas::testing::BevyTestApp;
+
+#[derive(Resource, Clone, Debug, PartialEq)]
+struct Volume(f32);
+
+#[test]
+fn disposed_signal_is_removed_and_a_new_one_attached() {
+    let mut test_app = BevyTestApp::new();
+
+    let (attach_volume, bevy_attach_volume) =
+        test_app.leptos(message_l2b::<BevyMessageDuplex<Volume>>);
+
+    let first = test_app.leptos(Owner::new);
+    let (_, bevy_volume) = first.with(|| signal_synced(Volume(0.5)));
+
+    test_app
+        .app_mut()
+        .sync_leptos_signal_with_resource(bevy_volume)
+        .attach_bridges_from_leptos(bevy_attach_volume);
+
+    test_app.update();
+    assert_eq!(*test_app.resource::<Volume>(), Volume(0.5));
+
+    first.cleanup();
+    test_app.update();
+    assert!(!test_app
+        .world()
+        .contains_resource::<BevyMessageDuplex<Volume>>());
+
+    let second = test_app.leptos(Owner::new);
+    let (volume, bevy_volume) = second.with(|| signal_synced(Volume(0.8)));
+    test_app.leptos(|| attach_volume.send(bevy_volume).unwrap());
+
+    test_app.update();
+    assert_eq!(*test_app.resource::<Volume>(), Volume(0.8));
+
+    test_app.world_mut().resource_mut::<Volume>().0 = 1.0;
+    test_app.update();
+    assert_eq!(volume.get_untracked(), Volume(1.0));
+}
+
+#[test]
+fn sending_to_a_disposed_signal_fails() {
+    let test_app = BevyTestApp::new();
+
+    let owner = test_app.leptos(Owner::new);
+    let (clicked, _bevy_clicked) = owner.with(message_l2b::<u32>);
+    let tx = clicked.tx().get_value();
+
+    owner.cleanup();
+
+    assert!(tx.is_closed());
+    assert!(tx.send(1).is_err());
+}
@@ -0,0 +1,69 @@
+use leptos_bevy_canvas::prelude::*;
+use std::collections::HashMap;
+
+fn roundtrip<T: Diffable + PartialEq + std::fmt::Debug>(old: T, new: T) {
+    let patch = new.diff(&old).expect("values differ");
+
+    let mut patched = old;
+    patched.apply_patch(patch).unwrap();
+
+    assert_eq!(patched, new);
+}
+
+#[test]
+fn vec_shrinks() {
+    roundtrip(vec![1, 2, 3, 4], vec![1, 5]);
+    roundtrip(vec![1, 2, 3], vec![]);
+}
+
+#[test]
+fn vec_grows() {
+    roundtrip(vec![1], vec![2, 3, 4]);
+    roundtrip(vec![], vec![1, 2]);
+}
+
+#[test]
+fn vec_reorders() {
+    roundtrip(vec![1, 2, 3], vec![3, 1, 2]);
+    roundtrip(vec![vec![1], vec![2, 3]], vec![vec![2, 3], vec![1]]);
+}
+
+#[test]
+fn vec_patch_needs_the_same_length() {
+    let patch = vec![1, 2].diff(&vec![1]).unwrap();
+
+    assert_eq!(vec![1, 2, 3].apply_patch(patch), Err(PatchError));
+}
+
+#[test]
+fn hash_map_removes_changes_and_inserts_keys() {
+    let old = HashMap::from([("a", 1), ("b", 2), ("c", 3)]);
+    let new = HashMap::from([("b", 20), ("c", 3), ("d", 4)]);
+
+    roundtrip(old, new);
+}
+
+#[test]
+fn hash_map_removes_all_keys() {
+    roundtrip(HashMap::from([("a", 1), ("b", 2)]), HashMap::new());
+}
+
+#[test]
+fn hash_map_patch_needs_the_removed_key() {
+    let patch = HashMap::from([("b", 2)])
+        .diff(&HashMap::from([("a", 1), ("b", 2)]))
+        .unwrap();
+
+    assert_eq!(
+        HashMap::from([("b", 2)]).apply_patch(patch),
+        Err(PatchError)
+    );
+}
+
+#[test]
+fn unchanged_values_have_no_patch() {
+    assert!(vec![1, 2].diff(&vec![1, 2]).is_none());
+    assert!(HashMap::from([("a", 1)])
+        .diff(&HashMap::from([("a", 1)]))
+        .is_none());
+}
@@ -0,0 +1,101 @@
+#![cfg(feature = "testing")]
+
+use bevy::prelude::*;
+use leptos::prelude::{GetUntracked, Update as _};
+use leptos_bevy_canvas::prelude::*;
+use leptos_bevy_canvas::testing::BevyTestApp;
+use std::collections::HashMap;
+
+#[derive(Resource, Diffable, Clone, Debug, Default, PartialEq)]
+struct Board {
+    cells: Vec<u32>,
+    owners: HashMap<String, u32>,
+}
+
+fn board() -> Board {
+    Board {
+        cells: vec![1, 2, 3, 4],
+        owners: HashMap::from([("a".to_string(), 1), ("b".to_string(), 2)]),
+    }
+}
+
+fn test_app() -> (BevyTestApp, RwSignalSynced<Board>) {
+    let mut test_app = BevyTestApp::new();
+
+    let (board, bevy_board) = test_app.leptos(|| diffed_signal_synced(board()));
+
+    test_app
+        .app_mut()
+        .sync_leptos_signal_with_diffed_resource(bevy_board);
+    test_app.update();
+
+    (test_app, board)
+}
+
+#[test]
+fn bevy_shrinks_and_reorders() {
+    let (mut test_app, board) = test_app();
+
+    test_app.world_mut().resource_mut::<Board>().cells = vec![4, 1];
+    test_app.update();
+
+    assert_eq!(board.get_untracked().cells, vec![4, 1]);
+}
+
+#[test]
+fn leptos_removes_keys() {
+    let (mut test_app, board) = test_app();
+
+    board.update(|board| {
+        board.owners.remove("a");
+        board.owners.insert("c".to_string(), 3);
+    });
+    test_app.update();
+
+    assert_eq!(
+        test_app.resource::<Board>().owners,
+        HashMap::from([("b".to_string(), 2), ("c".to_string(), 3)])
+    );
+}
+
+#[test]
+fn concurrent_changes_converge_to_bevy() {
+    let (mut test_app, board) = test_app();
+
+    board.update(|board| board.cells.truncate(1));
+    test_app.world_mut().resource_mut::<Board>().cells.push(5);
+
+    test_app.update_n(2);
+
+    let expected = vec![1, 2, 3, 4, 5];
+    assert_eq!(test_app.resource::<Board>().cells, expected);
+    assert_eq!(board.get_untracked().cells, expected);
+
+    // both sides keep syncing patches afterwards
+    board.update(|board| board.cells.reverse());
+    test_app.update();
+    assert_eq!(test_app.resource::<Board>().cells, vec![5, 4, 3, 2, 1]);
+
+    test_app.world_mut().resource_mut::<Board>().cells.pop();
+    test_app.update();
+    assert_eq!(board.get_untracked().cells, vec![5, 4, 3, 2]);
+}
+
+#[test]
+fn patches_are_retried_while_bevy_does_not_read() {
+    let (mut test_app, board) = test_app();
+
+    // Bevy doesn't run so the channel fills up but Leptos never blocks
+    for cell in 0..60 {
+        board.update(|board| board.cells.push(cell));
+        test_app.run_leptos_frame();
+    }
+
+    test_app.update_n(2);
+
+    let mut expected = self::board();
+    expected.cells.extend(0..60);
+
+    assert_eq!(test_app.resource::<Board>(), &expected);
+    assert_eq!(board.get_untracked(), expected);
+}
@@ -0,0 +1,197 @@
+#![cfg(feature = "testing")]
+
+use bevy::prelude::*;
+use leptos::prelude::{GetUntracked, Set};
+use leptos_bevy_canvas::prelude::*;
+use leptos_bevy_canvas::testing::BevyTestApp;
+
+#[derive(Resource, Clone, Debug, PartialEq)]
+struct Drawing(u32);
+
+fn setup(depth: usize) -> (BevyTestApp, RwSignalSynced<Drawing>, HistoryControls) {
+    let mut test_app = BevyTestApp::new();
+
+    let (drawing, bevy_drawing) = test_app.leptos(|| signal_synced(Drawing(0)));
+    let (history, bevy_history) = test_app.leptos(history_controls);
+
+    test_app
+        .app_mut()
+        .sync_leptos_signal_with_resource_with_options(
+            bevy_drawing,
+            SyncOptions::default().history(bevy_history, depth),
+        );
+    test_app.update();
+
+    (test_app, drawing, history)
+}
+
+fn change_in_bevy(test_app: &mut BevyTestApp, value: u32) {
+    test_app.world_mut().resource_mut::<Drawing>().0 = value;
+    test_app.update();
+}
+
+#[test]
+fn undo_and_redo_from_leptos() {
+    let (mut test_app, drawing, history) = setup(10);
+
+    assert!(!history.can_undo().get_untracked());
+
+    change_in_bevy(&mut test_app, 1);
+    change_in_bevy(&mut test_app, 2);
+
+    assert!(history.can_undo().get_untracked());
+    assert!(!history.can_redo().get_untracked());
+
+    history.undo();
+    test_app.update();
+
+    assert_eq!(test_app.resource::<Drawing>(), &Drawing(1));
+    assert_eq!(drawing.get_untracked(), Drawing(1));
+    assert!(history.can_redo().get_untracked());
+
+    history.undo();
+    test_app.update();
+
+    assert_eq!(test_app.resource::<Drawing>(), &Drawing(0));
+    assert_eq!(drawing.get_untracked(), Drawing(0));
+    assert!(!history.can_undo().get_untracked());
+
+    history.redo();
+    test_app.update();
+
+    assert_eq!(test_app.resource::<Drawing>(), &Drawing(1));
+    assert_eq!(drawing.get_untracked(), Drawing(1));
+    assert!(history.can_undo().get_untracked());
+    assert!(history.can_redo().get_untracked());
+}
+
+#[test]
+fn leptos_writes_are_recorded() {
+    let (mut test_app, drawing, history) = setup(10);
+
+    drawing.set(Drawing(5));
+    test_app.update();
+
+    assert_eq!(test_app.resource::<Drawing>(), &Drawing(5));
+    assert!(history.can_undo().get_untracked());
+
+    history.undo();
+    test_app.update();
+
+    assert_eq!(test_app.resource::<Drawing>(), &Drawing(0));
+    assert_eq!(drawing.get_untracked(), Drawing(0));
+}
+
+#[test]
+fn a_new_change_discards_the_redo_entries() {
+    let (mut test_app, _drawing, history) = setup(10);
+
+    change_in_bevy(&mut test_app, 1);
+
+    history.undo();
+    test_app.update();
+    assert!(history.can_redo().get_untracked());
+
+    change_in_bevy(&mut test_app, 2);
+    assert!(!history.can_redo().get_untracked());
+
+    history.redo();
+    test_app.update();
+    assert_eq!(test_app.resource::<Drawing>(), &Drawing(2));
+}
+
+#[test]
+fn grouped_changes_are_undone_together() {
+    let (mut test_app, drawing, history) = setup(10);
+
+    change_in_bevy(&mut test_app, 1);
+
+    history.begin_group();
+    test_app.update();
+
+    // e.g. a slider that is dragged over several frames
+    for value in 2..=4 {
+        drawing.set(Drawing(value));
+        test_app.update();
+    }
+
+    history.end_group();
+    test_app.update();
+
+    history.undo();
+    test_app.update();
+
+    assert_eq!(test_app.resource::<Drawing>(), &Drawing(1));
+    assert_eq!(drawing.get_untracked(), Drawing(1));
+
+    history.redo();
+    test_app.update();
+
+    assert_eq!(test_app.resource::<Drawing>(), &Drawing(4));
+}
+
+#[test]
+fn undo_closes_an_open_group() {
+    let (mut test_app, _drawing, history) = setup(10);
+
+    history.begin_group();
+    test_app.update();
+
+    change_in_bevy(&mut test_app, 1);
+    change_in_bevy(&mut test_app, 2);
+
+    history.undo();
+    test_app.update();
+
+    assert_eq!(test_app.resource::<Drawing>(), &Drawing(0));
+}
+
+#[test]
+fn history_from_bevy() {
+    let (mut test_app, drawing, history) = setup(10);
+
+    change_in_bevy(&mut test_app, 1);
+
+    test_app
+        .world_mut()
+        .resource_mut::<History<Drawing>>()
+        .undo();
+    test_app.update();
+
+    assert_eq!(test_app.resource::<Drawing>(), &Drawing(0));
+    assert_eq!(drawing.get_untracked(), Drawing(0));
+    assert!(history.can_redo().get_untracked());
+}
+
+#[test]
+fn only_depth_entries_are_kept() {
+    let (mut test_app, _drawing, history) = setup(2);
+
+    for value in 1..=4 {
+        change_in_bevy(&mut test_app, value);
+    }
+
+    for _ in 0..3 {
+        history.undo();
+        test_app.update();
+    }
+
+    assert_eq!(test_app.resource::<Drawing>(), &Drawing(2));
+    assert!(!history.can_undo().get_untracked());
+
+    for _ in 0..3 {
+        history.redo();
+        test_app.update();
+    }
+
+    assert_eq!(test_app.resource::<Drawing>(), &Drawing(4));
+    assert!(!history.can_redo().get_untracked());
+
+    // redoing doesn't grow the history beyond its depth
+    for _ in 0..3 {
+        history.undo();
+        test_app.update();
+    }
+
+    assert_eq!(test_app.resource::<Drawing>(), &Drawing(2));
+}
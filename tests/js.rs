@@ -0,0 +1,142 @@
+#![cfg(all(feature = "js", feature = "testing"))]
+
+use bevy::prelude::*;
+use leptos_bevy_canvas::js::{
+    expose_receiver_to_js, expose_sender_to_js, send_json, subscribe_json, unsubscribe_json,
+    JsSendError,
+};
+use leptos_bevy_canvas::prelude::*;
+use leptos_bevy_canvas::testing::BevyTestApp;
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+#[derive(Message, Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Clicked {
+    x: u32,
+}
+
+fn clicks(test_app: &BevyTestApp) -> Vec<Clicked> {
+    test_app
+        .world()
+        .resource::<Messages<Clicked>>()
+        .iter_current_update_messages()
+        .cloned()
+        .collect()
+}
+
+fn click_twice(mut clicked: MessageWriter<Clicked>) {
+    clicked.write_batch([Clicked { x: 1 }, Clicked { x: 2 }]);
+}
+
+#[test]
+fn json_is_sent_to_bevy() {
+    let mut test_app = BevyTestApp::new();
+
+    let bevy_clicked = test_app.leptos(|| {
+        let (clicked, bevy_clicked) = message_l2b::<Clicked>();
+        expose_sender_to_js("click", clicked);
+        bevy_clicked
+    });
+
+    test_app.app_mut().import_message_from_leptos(bevy_clicked);
+
+    send_json("click", r#"{ "x": 3 }"#).unwrap();
+    test_app.update();
+
+    assert_eq!(clicks(&test_app), vec![Clicked { x: 3 }]);
+}
+
+#[test]
+fn send_errors_are_told_apart() {
+    let mut test_app = BevyTestApp::new();
+
+    let (bevy_clicked, closed_bevy_clicked) = test_app.leptos(|| {
+        let (clicked, bevy_clicked) = message_l2b::<Clicked>();
+        expose_sender_to_js("click", clicked);
+
+        let (closed, closed_bevy_clicked) = message_l2b::<Clicked>();
+        expose_sender_to_js("closed", closed);
+
+        (bevy_clicked, closed_bevy_clicked)
+    });
+
+    test_app.app_mut().import_message_from_leptos(bevy_clicked);
+
+    assert_eq!(
+        send_json("unknown", r#"{ "x": 1 }"#),
+        Err(JsSendError::UnknownChannel("unknown".to_string()))
+    );
+    assert!(matches!(
+        send_json("click", r#"{ "y": 1 }"#),
+        Err(JsSendError::InvalidJson(_))
+    ));
+
+    // Bevy doesn't read before the next update
+    while send_json("click", r#"{ "x": 1 }"#).is_ok() {}
+    assert_eq!(send_json("click", r#"{ "x": 1 }"#), Err(JsSendError::Full));
+
+    drop(closed_bevy_clicked);
+    assert_eq!(
+        send_json("closed", r#"{ "x": 1 }"#),
+        Err(JsSendError::Closed)
+    );
+}
+
+#[test]
+fn sender_is_removed_on_cleanup() {
+    let test_app = BevyTestApp::new();
+
+    let (owner, _bevy_clicked) = test_app.leptos(|| {
+        let owner = leptos::prelude::Owner::new();
+        let bevy_clicked = owner.with(|| {
+            let (clicked, bevy_clicked) = message_l2b::<Clicked>();
+            expose_sender_to_js("click", clicked);
+            bevy_clicked
+        });
+
+        (owner, bevy_clicked)
+    });
+
+    assert_eq!(send_json("click", r#"{ "x": 1 }"#), Ok(()));
+
+    owner.cleanup();
+
+    assert_eq!(
+        send_json("click", r#"{ "x": 1 }"#),
+        Err(JsSendError::UnknownChannel("click".to_string()))
+    );
+}
+
+#[test]
+fn subscribers_receive_every_message_as_json() {
+    let mut test_app = BevyTestApp::new();
+
+    let bevy_clicked = test_app.leptos(|| {
+        let (clicked, bevy_clicked) = message_b2l::<Clicked>();
+        expose_receiver_to_js("click", clicked);
+        bevy_clicked
+    });
+
+    test_app
+        .app_mut()
+        .export_message_to_leptos(bevy_clicked)
+        .add_systems(Update, click_twice);
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let id = subscribe_json("click", {
+        let received = Arc::clone(&received);
+        move |json| received.lock().unwrap().push(json.to_string())
+    });
+
+    test_app.update();
+
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![r#"{"x":1}"#.to_string(), r#"{"x":2}"#.to_string()]
+    );
+
+    unsubscribe_json("click", id);
+    test_app.update();
+
+    assert_eq!(received.lock().unwrap().len(), 2);
+}
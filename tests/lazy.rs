@@ -0,0 +1,78 @@
+#![cfg(feature = "testing")]
+
+use bevy::prelude::*;
+use leptos::prelude::{GetUntracked, Update as _};
+use leptos_bevy_canvas::prelude::*;
+use leptos_bevy_canvas::testing::BevyTestApp;
+
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+struct Stats {
+    kills: u32,
+    name: String,
+}
+
+#[derive(Resource, Default)]
+struct KillNow(bool);
+
+fn kill(mut kill_now: ResMut<KillNow>, mut stats: ResMut<Stats>) {
+    if std::mem::take(&mut kill_now.0) {
+        stats.kills += 1;
+    }
+}
+
+#[test]
+fn unobserved_write_does_not_overwrite_newer_bevy_value() {
+    let mut test_app = BevyTestApp::new();
+
+    let (stats, bevy_stats) = test_app.leptos(|| signal_synced(Stats::default()));
+
+    test_app
+        .app_mut()
+        .sync_leptos_signal_with_resource(bevy_stats.lazy())
+        .init_resource::<KillNow>()
+        .add_systems(Update, kill);
+
+    test_app.update();
+
+    test_app.world_mut().resource_mut::<KillNow>().0 = true;
+    test_app.update();
+
+    // nothing observes the signal so the kill wasn't sent
+    assert_eq!(stats.get_untracked().kills, 0);
+
+    stats.update(|stats| stats.name = "Ferris".to_string());
+    test_app.update();
+
+    // the write was based on an outdated value so Bevy kept its value and resynced Leptos
+    assert_eq!(test_app.resource::<Stats>().kills, 1);
+    assert_eq!(stats.get_untracked().kills, 1);
+
+    stats.update(|stats| stats.name = "Ferris".to_string());
+    test_app.update();
+
+    assert_eq!(
+        test_app.resource::<Stats>(),
+        &Stats {
+            kills: 1,
+            name: "Ferris".to_string(),
+        }
+    );
+}
+
+#[test]
+fn unobserved_write_is_applied_without_bevy_changes() {
+    let mut test_app = BevyTestApp::new();
+
+    let (stats, bevy_stats) = test_app.leptos(|| signal_synced(Stats::default()));
+
+    test_app
+        .app_mut()
+        .sync_leptos_signal_with_resource(bevy_stats.lazy());
+
+    test_app.update();
+
+    stats.update(|stats| stats.name = "Ferris".to_string());
+    test_app.update();
+
+    assert_eq!(test_app.resource::<Stats>().name, "Ferris");
+}
@@ -0,0 +1,173 @@
+#![cfg(feature = "testing")]
+
+use bevy::prelude::*;
+use crossbeam_channel::TrySendError;
+use leptos::prelude::GetUntracked;
+use leptos_bevy_canvas::prelude::*;
+use leptos_bevy_canvas::testing::BevyTestApp;
+use std::sync::{Arc, Mutex};
+
+#[derive(Message, Clone, Debug, PartialEq)]
+struct Scored(u32);
+
+#[derive(Message, Clone, Debug, PartialEq)]
+struct Clicked(u32);
+
+fn score_three_times(mut scored: MessageWriter<Scored>) {
+    scored.write_batch([Scored(1), Scored(2), Scored(3)]);
+}
+
+#[test]
+fn on_message_receives_every_message_of_a_frame() {
+    let mut test_app = BevyTestApp::new();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let (scored, bevy_scored) = test_app.leptos(|| {
+        let (scored, bevy_scored) = message_b2l::<Scored>();
+
+        let received = Arc::clone(&received);
+        scored.on_message(move |message| received.lock().unwrap().push(message.0));
+
+        (scored, bevy_scored)
+    });
+
+    test_app
+        .app_mut()
+        .export_message_to_leptos(bevy_scored)
+        .add_systems(Update, score_three_times);
+
+    test_app.update();
+
+    assert_eq!(*received.lock().unwrap(), vec![1, 2, 3]);
+    assert_eq!(scored.get_untracked(), Some(Scored(3)));
+}
+
+#[test]
+fn send_fails_instead_of_blocking_when_bevy_does_not_read() {
+    let mut test_app = BevyTestApp::new();
+
+    let (clicked, bevy_clicked) = test_app.leptos(message_l2b::<Clicked>);
+
+    test_app.app_mut().import_message_from_leptos(bevy_clicked);
+
+    for click in 0..50 {
+        clicked.send(Clicked(click)).unwrap();
+    }
+
+    assert_eq!(
+        clicked.try_send(Clicked(50)),
+        Err(TrySendError::Full(Clicked(50)))
+    );
+    assert!(clicked.send(Clicked(50)).is_err());
+
+    test_app.update();
+
+    assert!(clicked.send(Clicked(50)).is_ok());
+}
+
+#[test]
+fn messages_that_do_not_fit_are_sent_in_the_next_frame() {
+    let mut test_app = BevyTestApp::new();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let bevy_scored = test_app.leptos(|| {
+        let (scored, bevy_scored) = message_b2l::<Scored>();
+
+        let received = Arc::clone(&received);
+        scored.on_message(move |message| received.lock().unwrap().push(message.0));
+
+        bevy_scored
+    });
+
+    test_app
+        .app_mut()
+        .export_message_to_leptos(bevy_scored)
+        .add_systems(Startup, |mut scored: MessageWriter<Scored>| {
+            scored.write_batch((0..60).map(Scored));
+        });
+
+    test_app.update();
+    assert_eq!(received.lock().unwrap().len(), 50);
+
+    test_app.update();
+    assert_eq!(*received.lock().unwrap(), (0..60).collect::<Vec<_>>());
+}
+
+#[derive(Message, Debug)]
+struct Frame(Vec<u8>);
+
+#[test]
+fn owned_messages_are_moved_without_touching_other_readers() {
+    let mut test_app = BevyTestApp::new();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let bevy_frames = test_app.leptos(|| {
+        let (frames, bevy_frames) = message_b2l::<Frame>();
+
+        let received = Arc::clone(&received);
+        frames.on_message(move |frame| received.lock().unwrap().push(frame.0.clone()));
+
+        bevy_frames
+    });
+
+    let read_in_last = Arc::new(Mutex::new(Vec::new()));
+    let read = Arc::clone(&read_in_last);
+
+    test_app
+        .app_mut()
+        .add_message::<Frame>()
+        .export_owned_message_to_leptos(bevy_frames)
+        .add_systems(
+            Update,
+            |mut to_leptos: MessageWriter<ToLeptos<Frame>>, mut frames: MessageWriter<Frame>| {
+                to_leptos.write(ToLeptos(Frame(vec![1, 2])));
+                frames.write(Frame(vec![3]));
+            },
+        )
+        .add_systems(Last, move |mut frames: MessageReader<Frame>| {
+            read.lock()
+                .unwrap()
+                .extend(frames.read().map(|frame| frame.0.clone()));
+        });
+
+    test_app.update();
+
+    assert_eq!(*received.lock().unwrap(), vec![vec![1, 2]]);
+    assert_eq!(*read_in_last.lock().unwrap(), vec![vec![3]]);
+}
+
+#[test]
+fn fixed_update_import_runs_in_fixed_pre_update() {
+    let mut test_app = BevyTestApp::new();
+
+    let (clicked, bevy_clicked) = test_app.leptos(message_l2b::<Clicked>);
+
+    test_app
+        .app_mut()
+        // no fixed tick runs on its own during the test
+        .insert_resource(Time::<Fixed>::from_seconds(1000.0))
+        .import_message_from_leptos_with_options(
+            bevy_clicked,
+            MessageOptions::default().fixed_update(),
+        );
+
+    clicked.send(Clicked(1)).unwrap();
+    test_app.update();
+
+    let clicks = |test_app: &BevyTestApp| {
+        test_app
+            .world()
+            .resource::<Messages<Clicked>>()
+            .iter_current_update_messages()
+            .cloned()
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(clicks(&test_app), vec![]);
+
+    test_app.world_mut().run_schedule(FixedPreUpdate);
+    assert_eq!(clicks(&test_app), vec![Clicked(1)]);
+}
@@ -0,0 +1,69 @@
+//! Drives the bridge natively with `run_frame` without the `testing` feature.
+
+#![cfg(all(not(target_arch = "wasm32"), not(feature = "ssr")))]
+
+use bevy::prelude::*;
+use leptos::prelude::{Get, GetUntracked, Owner, Set};
+use leptos_bevy_canvas::prelude::*;
+use std::time::{Duration, Instant};
+
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+struct Score(u32);
+
+fn answer_one_with_two(mut score: ResMut<Score>) {
+    if score.0 == 1 {
+        score.0 = 2;
+    }
+}
+
+#[test]
+fn run_frame_sends_and_applies() {
+    let owner = Owner::new();
+    let (score, bevy_score) = owner.with(|| signal_synced(Score::default()));
+
+    let mut app = App::new();
+    app.add_plugins(MinimalPlugins)
+        .sync_leptos_signal_with_resource(bevy_score)
+        .add_systems(Update, answer_one_with_two);
+    app.finish();
+    app.cleanup();
+
+    score.set(Score(1));
+    app.update();
+    // nothing is sent before a Leptos frame ran
+    assert_eq!(app.world().resource::<Score>(), &Score(0));
+
+    owner.with(leptos_bevy_canvas::run_frame);
+    app.update();
+    assert_eq!(app.world().resource::<Score>(), &Score(2));
+
+    // the answer of `Update` is sent to Leptos in the same Bevy frame
+    owner.with(leptos_bevy_canvas::run_frame);
+    assert_eq!(score.get_untracked(), Score(2));
+}
+
+#[test]
+fn run_frame_drives_bevy_thread() {
+    let owner = Owner::new();
+    let (score, bevy_score) = owner.with(|| signal_synced(Score::default()));
+
+    let bevy_thread = BevyThread::spawn(move || {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .sync_leptos_signal_with_resource(bevy_score)
+            .add_systems(Update, answer_one_with_two);
+        app
+    });
+
+    score.set(Score(1));
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while owner.with(|| score.get()) != Score(2) {
+        assert!(Instant::now() < deadline, "Bevy never answered");
+
+        owner.with(leptos_bevy_canvas::run_frame);
+        std::thread::sleep(Duration::from_millis(1));
+    }
+
+    bevy_thread.stop().expect("Bevy thread panicked");
+}
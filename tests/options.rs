@@ -0,0 +1,284 @@
+#![cfg(feature = "testing")]
+
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use leptos::prelude::{Get, GetUntracked, Set};
+use leptos::reactive::effect::ImmediateEffect;
+use leptos_bevy_canvas::prelude::*;
+use leptos_bevy_canvas::testing::BevyTestApp;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+struct Score(u32);
+
+#[derive(Resource, Diffable, Clone, Debug, Default, PartialEq)]
+struct Board {
+    cells: Vec<u32>,
+}
+
+#[derive(Resource, Default)]
+struct Paused(bool);
+
+fn not_paused(paused: Res<Paused>) -> bool {
+    !paused.0
+}
+
+fn paused_test_app() -> BevyTestApp {
+    let mut test_app = BevyTestApp::new();
+    test_app.app_mut().insert_resource(Paused(true));
+    test_app
+}
+
+#[test]
+fn optional_resource_sync_respects_run_conditions() {
+    let mut test_app = paused_test_app();
+
+    let (score, bevy_score) = test_app.leptos(|| signal_synced(None::<Score>));
+
+    test_app
+        .app_mut()
+        .sync_leptos_signal_with_optional_resource_with_options(
+            bevy_score,
+            SyncOptions::default().run_if(not_paused),
+        );
+    test_app.update();
+
+    score.set(Some(Score(1)));
+    test_app.update();
+    assert!(test_app.world().get_resource::<Score>().is_none());
+
+    test_app.world_mut().resource_mut::<Paused>().0 = false;
+    test_app.update();
+    assert_eq!(test_app.resource::<Score>(), &Score(1));
+}
+
+#[test]
+fn resource_field_sync_respects_run_conditions() {
+    let mut test_app = paused_test_app();
+
+    let (score, bevy_score) = test_app.leptos(|| signal_synced(0));
+
+    test_app
+        .app_mut()
+        .init_resource::<Score>()
+        .sync_leptos_signal_with_resource_field_with_options(
+            bevy_score,
+            |score: &Score| &score.0,
+            |score: &mut Score| &mut score.0,
+            SyncOptions::default().run_if(not_paused),
+        );
+    test_app.update();
+
+    score.set(3);
+    test_app.update();
+    assert_eq!(test_app.resource::<Score>(), &Score(0));
+
+    test_app.world_mut().resource_mut::<Paused>().0 = false;
+    test_app.update();
+    assert_eq!(test_app.resource::<Score>(), &Score(3));
+}
+
+#[test]
+fn diffed_sync_respects_run_conditions() {
+    let mut test_app = paused_test_app();
+
+    let (board, bevy_board) = test_app.leptos(|| diffed_signal_synced(Board::default()));
+
+    test_app
+        .app_mut()
+        .sync_leptos_signal_with_diffed_resource_with_options(
+            bevy_board,
+            SyncOptions::default().run_if(not_paused),
+        );
+    test_app.update();
+
+    test_app.world_mut().resource_mut::<Board>().cells = vec![1, 2];
+    test_app.update();
+    assert_eq!(board.get_untracked(), Board::default());
+
+    test_app.world_mut().resource_mut::<Paused>().0 = false;
+    test_app.update();
+    assert_eq!(board.get_untracked().cells, vec![1, 2]);
+}
+
+#[test]
+fn resource_export_runs_in_the_export_schedule() {
+    let mut test_app = BevyTestApp::new();
+
+    let (score, bevy_score) = test_app.leptos(resource_signal::<Score>);
+
+    test_app
+        .app_mut()
+        .insert_resource(Score(0))
+        .export_resource_to_leptos_with_options(
+            bevy_score,
+            MessageOptions::default().export_schedule(First),
+        );
+    test_app.update();
+    assert_eq!(score.get_untracked(), Some(Score(0)));
+
+    test_app.world_mut().resource_mut::<Score>().0 = 1;
+    test_app.update();
+    assert_eq!(score.get_untracked(), Some(Score(1)));
+}
+
+fn clamp_score(_: &Score, new: &mut Score) -> Validation {
+    match new.0 {
+        0..=10 => Validation::Valid,
+        11..=100 => {
+            new.0 = 10;
+            Validation::Corrected
+        }
+        _ => Validation::Invalid,
+    }
+}
+
+#[test]
+fn validator_corrects_and_rejects_values_from_leptos() {
+    let mut test_app = BevyTestApp::new();
+
+    let (score, bevy_score) = test_app.leptos(|| signal_synced(Score(0)));
+
+    test_app
+        .app_mut()
+        .sync_leptos_signal_with_resource_with_options(
+            bevy_score,
+            SyncOptions::default().validator(clamp_score),
+        );
+    test_app.update();
+
+    score.set(Score(5));
+    test_app.update();
+    assert_eq!(test_app.resource::<Score>(), &Score(5));
+    assert_eq!(score.get_untracked(), Score(5));
+
+    // the corrected value is applied and sent back to Leptos
+    score.set(Score(50));
+    test_app.update();
+    assert_eq!(test_app.resource::<Score>(), &Score(10));
+    assert_eq!(score.get_untracked(), Score(10));
+
+    // the invalid value is discarded and Leptos gets the Bevy value back
+    score.set(Score(500));
+    test_app.update();
+    assert_eq!(test_app.resource::<Score>(), &Score(10));
+    assert_eq!(score.get_untracked(), Score(10));
+}
+
+#[test]
+#[should_panic(expected = "`SyncOptions::validator` isn't supported by \
+                           `sync_leptos_signal_with_optional_resource`")]
+fn optional_resource_sync_rejects_a_validator() {
+    let mut test_app = BevyTestApp::new();
+
+    let (_score, bevy_score) = test_app.leptos(|| signal_synced(None::<Score>));
+
+    test_app
+        .app_mut()
+        .sync_leptos_signal_with_optional_resource_with_options(
+            bevy_score,
+            SyncOptions::default().validator(|_, _| Validation::Valid),
+        );
+}
+
+#[test]
+#[should_panic(expected = "`SyncOptions::max_send_rate` isn't supported by \
+                           `sync_leptos_signal_with_resource_field`")]
+fn resource_field_sync_rejects_a_max_send_rate() {
+    let mut test_app = BevyTestApp::new();
+
+    let (_score, bevy_score) = test_app.leptos(|| signal_synced(0));
+
+    test_app
+        .app_mut()
+        .init_resource::<Score>()
+        .sync_leptos_signal_with_resource_field_with_options(
+            bevy_score,
+            |score: &Score| &score.0,
+            |score: &mut Score| &mut score.0,
+            SyncOptions::default().max_send_rate(10.0),
+        );
+}
+
+#[test]
+#[should_panic(expected = "`SyncOptions::policy` isn't supported by \
+                           `sync_leptos_signal_with_diffed_resource`")]
+fn diffed_sync_rejects_a_policy() {
+    let mut test_app = BevyTestApp::new();
+
+    let (_board, bevy_board) = test_app.leptos(|| diffed_signal_synced(Board::default()));
+
+    test_app
+        .app_mut()
+        .sync_leptos_signal_with_diffed_resource_with_options(
+            bevy_board,
+            SyncOptions::default().policy(SyncPolicy::BevyWins),
+        );
+}
+
+#[derive(Component, Clone, Debug, PartialEq)]
+struct Health(u32);
+
+#[test]
+#[should_panic(expected = "`SyncOptions::history` isn't supported by \
+                           `sync_leptos_signal_with_query`")]
+fn query_sync_rejects_a_history() {
+    let mut test_app = BevyTestApp::new();
+
+    let (_health, bevy_health) = test_app.leptos(single_query_signal::<(Health,), ()>);
+    let (_history, bevy_history) = test_app.leptos(history_controls);
+
+    test_app
+        .app_mut()
+        .sync_leptos_signal_with_query_with_options(
+            bevy_health,
+            SyncOptions::default().history(bevy_history, 10),
+        );
+}
+
+#[test]
+fn max_send_rate_sends_only_the_latest_value_of_an_interval() {
+    let mut test_app = BevyTestApp::new();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let (bevy_score, _effect) = test_app.leptos(|| {
+        let (score, bevy_score) = signal_synced(Score(0));
+
+        let received = Arc::clone(&received);
+        let effect = ImmediateEffect::new(move || {
+            received.lock().unwrap().push(score.get());
+        });
+
+        (bevy_score, effect)
+    });
+
+    test_app
+        .app_mut()
+        // every frame takes 100ms
+        .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_millis(
+            100,
+        )))
+        .sync_leptos_signal_with_resource_with_options(
+            bevy_score,
+            SyncOptions::default().max_send_rate(2.0),
+        );
+    test_app.update();
+    received.lock().unwrap().clear();
+
+    // the first change is sent right away
+    test_app.world_mut().resource_mut::<Score>().0 = 1;
+    test_app.update();
+    assert_eq!(*received.lock().unwrap(), vec![Score(1)]);
+
+    // the following changes are within 500ms of the first send
+    for score in 2..=4 {
+        test_app.world_mut().resource_mut::<Score>().0 = score;
+        test_app.update();
+    }
+    assert_eq!(*received.lock().unwrap(), vec![Score(1)]);
+
+    test_app.update_n(3);
+    assert_eq!(*received.lock().unwrap(), vec![Score(1), Score(4)]);
+}
@@ -0,0 +1,182 @@
+#![cfg(all(feature = "persist", feature = "testing"))]
+
+use bevy::prelude::*;
+use leptos::prelude::{GetUntracked, Set};
+use leptos_bevy_canvas::persist::{FileStorage, MemoryStorage, SyncStorage};
+use leptos_bevy_canvas::prelude::*;
+use leptos_bevy_canvas::testing::BevyTestApp;
+use serde::{Deserialize, Serialize};
+
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Volume(u32);
+
+fn app_with_storage(
+    storage: impl SyncStorage,
+    options: impl FnOnce(SyncOptions<Volume>) -> SyncOptions<Volume>,
+) -> (BevyTestApp, RwSignalSynced<Volume>) {
+    let mut test_app = BevyTestApp::new();
+
+    let (volume, bevy_volume) = test_app.leptos(|| signal_synced(Volume(5)));
+
+    test_app
+        .app_mut()
+        .sync_leptos_signal_with_resource_with_options(
+            bevy_volume,
+            options(SyncOptions::default().persist(storage, "volume")),
+        );
+    test_app.update();
+
+    (test_app, volume)
+}
+
+#[test]
+fn changes_are_saved() {
+    let storage = MemoryStorage::default();
+    let (mut test_app, volume) = app_with_storage(storage.clone(), |options| options);
+
+    test_app.world_mut().resource_mut::<Volume>().0 = 7;
+    test_app.update();
+
+    assert_eq!(storage.get("volume@v0").as_deref(), Some("7"));
+
+    volume.set(Volume(9));
+    test_app.update();
+
+    assert_eq!(storage.get("volume@v0").as_deref(), Some("9"));
+}
+
+#[test]
+fn saved_value_is_loaded_on_reload() {
+    let storage = MemoryStorage::default();
+
+    {
+        let (mut test_app, _volume) = app_with_storage(storage.clone(), |options| options);
+        test_app.world_mut().resource_mut::<Volume>().0 = 8;
+        test_app.update();
+    }
+
+    let (test_app, volume) = app_with_storage(storage, |options| options);
+
+    assert_eq!(test_app.resource::<Volume>(), &Volume(8));
+    assert_eq!(volume.get_untracked(), Volume(8));
+}
+
+#[test]
+fn values_of_another_version_are_ignored() {
+    let storage = MemoryStorage::default();
+    storage.set("volume@v1", "8");
+
+    let (mut test_app, volume) =
+        app_with_storage(storage.clone(), |options| options.persist_version(2));
+
+    assert_eq!(test_app.resource::<Volume>(), &Volume(5));
+    assert_eq!(volume.get_untracked(), Volume(5));
+
+    test_app.world_mut().resource_mut::<Volume>().0 = 6;
+    test_app.update();
+
+    assert_eq!(storage.get("volume@v1").as_deref(), Some("8"));
+    assert_eq!(storage.get("volume@v2").as_deref(), Some("6"));
+}
+
+#[test]
+fn value_that_cant_be_decoded_is_ignored() {
+    let storage = MemoryStorage::default();
+    storage.set("volume@v0", "\"loud\"");
+
+    let (test_app, volume) = app_with_storage(storage, |options| options);
+
+    assert_eq!(test_app.resource::<Volume>(), &Volume(5));
+    assert_eq!(volume.get_untracked(), Volume(5));
+}
+
+#[test]
+fn invalid_value_is_ignored() {
+    let storage = MemoryStorage::default();
+    storage.set("volume@v0", "80");
+
+    let (test_app, volume) = app_with_storage(storage, |options| {
+        options.validator(|_, new| {
+            if new.0 <= 10 {
+                Validation::Valid
+            } else {
+                Validation::Invalid
+            }
+        })
+    });
+
+    assert_eq!(test_app.resource::<Volume>(), &Volume(5));
+    assert_eq!(volume.get_untracked(), Volume(5));
+}
+
+#[test]
+fn corrected_value_is_loaded() {
+    let storage = MemoryStorage::default();
+    storage.set("volume@v0", "80");
+
+    let (test_app, volume) = app_with_storage(storage, |options| {
+        options.validator(|_, new| {
+            if new.0 <= 10 {
+                Validation::Valid
+            } else {
+                new.0 = 10;
+                Validation::Corrected
+            }
+        })
+    });
+
+    assert_eq!(test_app.resource::<Volume>(), &Volume(10));
+    assert_eq!(volume.get_untracked(), Volume(10));
+}
+
+#[test]
+fn file_storage_saves_and_reloads() {
+    let dir = std::env::temp_dir().join(format!("leptos-bevy-canvas-{}", std::process::id()));
+    let storage = FileStorage::new(&dir);
+
+    {
+        let (mut test_app, _volume) = app_with_storage(storage.clone(), |options| options);
+        test_app.world_mut().resource_mut::<Volume>().0 = 3;
+        test_app.update();
+    }
+
+    assert_eq!(
+        std::fs::read_to_string(dir.join("volume@v0.json")).unwrap(),
+        "3"
+    );
+
+    let (test_app, volume) = app_with_storage(storage, |options| options);
+
+    assert_eq!(test_app.resource::<Volume>(), &Volume(3));
+    assert_eq!(volume.get_untracked(), Volume(3));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn file_storage_keys_cant_escape_the_directory() {
+    let parent =
+        std::env::temp_dir().join(format!("leptos-bevy-canvas-escape-{}", std::process::id()));
+    let dir = parent.join("storage");
+    let storage = FileStorage::new(&dir);
+
+    storage.set("../outside", "1");
+    storage.set("/absolute", "2");
+
+    assert_eq!(storage.get("../outside").as_deref(), Some("1"));
+    assert_eq!(storage.get("/absolute").as_deref(), Some("2"));
+    assert!(!parent.join("outside.json").exists());
+
+    let mut files = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    files.sort();
+
+    assert_eq!(files, ["%2E%2E%2Foutside.json", "%2Fabsolute.json"]);
+
+    storage.remove("../outside");
+    assert_eq!(storage.get("../outside"), None);
+
+    std::fs::remove_dir_all(parent).unwrap();
+}
@@ -0,0 +1,328 @@
+#![cfg(all(feature = "record", feature = "testing"))]
+
+use bevy::prelude::*;
+use leptos::prelude::Set;
+use leptos_bevy_canvas::prelude::*;
+use leptos_bevy_canvas::record::{BridgeDirection, BridgeLog, BridgeRecorder, BridgeReplay};
+use leptos_bevy_canvas::testing::BevyTestApp;
+use serde::{Deserialize, Serialize};
+
+#[derive(Message, Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Click(u32);
+
+#[derive(Resource, Clone, Debug, PartialEq, Serialize, Deserialize)]
+struct Score(u32);
+
+#[derive(Resource, Debug, PartialEq)]
+struct Settings {
+    volume: u32,
+}
+
+fn take_log(test_app: &mut BevyTestApp) -> BridgeLog {
+    test_app
+        .world_mut()
+        .remove_resource::<BridgeRecorder>()
+        .unwrap()
+        .into_log()
+}
+
+fn clicks(test_app: &BevyTestApp) -> Vec<Click> {
+    test_app
+        .world()
+        .resource::<Messages<Click>>()
+        .iter_current_update_messages()
+        .cloned()
+        .collect()
+}
+
+#[test]
+fn bridges_of_the_same_type_are_recorded_separately() {
+    let mut test_app = BevyTestApp::new();
+
+    let (click_sender, bevy_click_receiver) = test_app.leptos(message_l2b::<Click>);
+    let (click_duplex, bevy_click_duplex) = test_app.leptos(message_duplex::<Click>);
+
+    test_app
+        .app_mut()
+        .import_message_from_leptos_with_options(
+            bevy_click_receiver,
+            MessageOptions::default().record::<Click>("clicks"),
+        )
+        .add_duplex_leptos_message_with_options(
+            bevy_click_duplex,
+            MessageOptions::default().record::<Click>("duplex clicks"),
+        )
+        .insert_resource(BridgeRecorder::default());
+
+    click_sender.send(Click(1)).unwrap();
+    click_duplex.send(Click(2)).unwrap();
+    test_app.update();
+
+    let log = test_app.resource::<BridgeRecorder>().log();
+
+    let receiver_values = log
+        .values(0, BridgeDirection::LeptosToBevy, "clicks")
+        .cloned()
+        .collect::<Vec<_>>();
+    let duplex_values = log
+        .values(0, BridgeDirection::LeptosToBevy, "duplex clicks")
+        .cloned()
+        .collect::<Vec<_>>();
+
+    assert_eq!(receiver_values, vec![serde_json::json!(1)]);
+    assert_eq!(duplex_values, vec![serde_json::json!(2)]);
+}
+
+#[test]
+fn only_the_values_of_the_channel_are_replayed() {
+    let mut test_app = BevyTestApp::new();
+
+    let (click_sender, bevy_click_receiver) = test_app.leptos(message_l2b::<Click>);
+    let (click_duplex, bevy_click_duplex) = test_app.leptos(message_duplex::<Click>);
+
+    test_app
+        .app_mut()
+        .import_message_from_leptos_with_options(
+            bevy_click_receiver,
+            MessageOptions::default().record::<Click>("clicks"),
+        )
+        .add_duplex_leptos_message_with_options(
+            bevy_click_duplex,
+            MessageOptions::default().record::<Click>("duplex clicks"),
+        )
+        .insert_resource(BridgeRecorder::default());
+
+    click_sender.send(Click(1)).unwrap();
+    click_duplex.send(Click(2)).unwrap();
+    test_app.update();
+
+    let log = take_log(&mut test_app);
+
+    let mut replay_app = BevyTestApp::new();
+    let (_click_sender, bevy_click_receiver) = replay_app.leptos(message_l2b::<Click>);
+
+    replay_app
+        .app_mut()
+        .import_message_from_leptos_with_options(
+            bevy_click_receiver,
+            MessageOptions::default().record::<Click>("clicks"),
+        )
+        .insert_resource(BridgeReplay::new(log));
+    replay_app.update();
+
+    assert_eq!(clicks(&replay_app), vec![Click(1)]);
+}
+
+#[test]
+#[should_panic(expected = "used by more than one bridge")]
+fn channel_names_are_unique() {
+    let mut test_app = BevyTestApp::new();
+
+    let (_click_sender, bevy_click_receiver) = test_app.leptos(message_l2b::<Click>);
+    let (_click_duplex, bevy_click_duplex) = test_app.leptos(message_duplex::<Click>);
+
+    test_app
+        .app_mut()
+        .import_message_from_leptos_with_options(
+            bevy_click_receiver,
+            MessageOptions::default().record::<Click>("clicks"),
+        )
+        .add_duplex_leptos_message_with_options(
+            bevy_click_duplex,
+            MessageOptions::default().record::<Click>("clicks"),
+        );
+}
+
+#[test]
+#[should_panic(expected = "has been created for")]
+fn message_type_has_to_match_the_bridge() {
+    let mut test_app = BevyTestApp::new();
+
+    let (_click_sender, bevy_click_receiver) = test_app.leptos(message_l2b::<Click>);
+
+    test_app.app_mut().import_message_from_leptos_with_options(
+        bevy_click_receiver,
+        MessageOptions::default().record::<Score>("clicks"),
+    );
+}
+
+#[test]
+fn initial_value_is_recorded_and_replayed() {
+    let mut test_app = BevyTestApp::new();
+
+    let (_score, bevy_score) = test_app.leptos(|| signal_synced(Score(3)));
+
+    test_app
+        .app_mut()
+        .sync_leptos_signal_with_resource_with_options(
+            bevy_score,
+            SyncOptions::default().record("score"),
+        )
+        .insert_resource(BridgeRecorder::default());
+
+    test_app.update();
+
+    let log = take_log(&mut test_app);
+
+    let initial = log
+        .values(0, BridgeDirection::LeptosToBevy, "score")
+        .cloned()
+        .collect::<Vec<_>>();
+    assert_eq!(initial, vec![serde_json::json!(3)]);
+
+    let mut replay_app = BevyTestApp::new();
+    let (_score, bevy_score) = replay_app.leptos(|| signal_synced(Score(0)));
+
+    replay_app
+        .app_mut()
+        .sync_leptos_signal_with_resource_with_options(
+            bevy_score,
+            SyncOptions::default().record("score"),
+        )
+        .insert_resource(BridgeReplay::new(log));
+    replay_app.update();
+
+    assert_eq!(replay_app.resource::<Score>(), &Score(3));
+}
+
+#[test]
+fn optional_resource_is_recorded_and_replayed() {
+    let mut test_app = BevyTestApp::new();
+
+    let (score, bevy_score) = test_app.leptos(|| signal_synced(None::<Score>));
+
+    test_app
+        .app_mut()
+        .sync_leptos_signal_with_optional_resource_with_options(
+            bevy_score,
+            SyncOptions::default().record("score"),
+        )
+        .insert_resource(BridgeRecorder::default());
+    test_app.update();
+
+    score.set(Some(Score(4)));
+    test_app.update();
+
+    score.set(None);
+    test_app.update();
+
+    let log = take_log(&mut test_app);
+
+    assert_eq!(
+        log.values(1, BridgeDirection::LeptosToBevy, "score")
+            .collect::<Vec<_>>(),
+        vec![&serde_json::json!(4)]
+    );
+
+    let mut replay_app = BevyTestApp::new();
+    let (_score, bevy_score) = replay_app.leptos(|| signal_synced(None::<Score>));
+
+    replay_app
+        .app_mut()
+        .sync_leptos_signal_with_optional_resource_with_options(
+            bevy_score,
+            SyncOptions::default().record("score"),
+        )
+        .insert_resource(BridgeReplay::new(log));
+
+    replay_app.update();
+    assert!(replay_app.world().get_resource::<Score>().is_none());
+
+    replay_app.update();
+    assert_eq!(replay_app.resource::<Score>(), &Score(4));
+
+    replay_app.update();
+    assert!(replay_app.world().get_resource::<Score>().is_none());
+}
+
+#[test]
+fn resource_field_is_recorded_and_replayed() {
+    let mut test_app = BevyTestApp::new();
+
+    let (volume, bevy_volume) = test_app.leptos(|| signal_synced(5));
+
+    test_app
+        .app_mut()
+        .insert_resource(Settings { volume: 5 })
+        .sync_leptos_signal_with_resource_field_with_options(
+            bevy_volume,
+            |settings: &Settings| &settings.volume,
+            |settings: &mut Settings| &mut settings.volume,
+            SyncOptions::default().record("volume"),
+        )
+        .insert_resource(BridgeRecorder::default());
+    test_app.update();
+
+    volume.set(7);
+    test_app.update();
+
+    let log = take_log(&mut test_app);
+
+    assert_eq!(
+        log.values(1, BridgeDirection::LeptosToBevy, "volume")
+            .collect::<Vec<_>>(),
+        vec![&serde_json::json!(7)]
+    );
+
+    let mut replay_app = BevyTestApp::new();
+    let (_volume, bevy_volume) = replay_app.leptos(|| signal_synced(5));
+
+    replay_app
+        .app_mut()
+        .insert_resource(Settings { volume: 5 })
+        .sync_leptos_signal_with_resource_field_with_options(
+            bevy_volume,
+            |settings: &Settings| &settings.volume,
+            |settings: &mut Settings| &mut settings.volume,
+            SyncOptions::default().record("volume"),
+        )
+        .insert_resource(BridgeReplay::new(log));
+
+    replay_app.update_n(2);
+    assert_eq!(replay_app.resource::<Settings>(), &Settings { volume: 7 });
+}
+
+#[test]
+fn messages_are_replayed_in_the_import_schedule() {
+    let mut test_app = BevyTestApp::new();
+
+    let (click_sender, bevy_click_receiver) = test_app.leptos(message_l2b::<Click>);
+
+    test_app
+        .app_mut()
+        .import_message_from_leptos_with_options(
+            bevy_click_receiver,
+            MessageOptions::default().record::<Click>("clicks"),
+        )
+        .insert_resource(BridgeRecorder::default());
+
+    click_sender.send(Click(1)).unwrap();
+    test_app.update();
+
+    let log = take_log(&mut test_app);
+
+    let mut replay_app = BevyTestApp::new();
+    let (_click_sender, bevy_click_receiver) = replay_app.leptos(message_l2b::<Click>);
+
+    replay_app
+        .app_mut()
+        // no fixed tick runs on its own during the test
+        .insert_resource(Time::<Fixed>::from_seconds(1000.0))
+        .import_message_from_leptos_with_options(
+            bevy_click_receiver,
+            MessageOptions::default()
+                .fixed_update()
+                .record::<Click>("clicks"),
+        )
+        .insert_resource(BridgeReplay::new(log));
+
+    replay_app.update();
+    assert_eq!(clicks(&replay_app), vec![]);
+
+    replay_app.world_mut().run_schedule(FixedPreUpdate);
+    assert_eq!(clicks(&replay_app), vec![Click(1)]);
+
+    // every recorded message is replayed only once
+    replay_app.world_mut().run_schedule(FixedPreUpdate);
+    assert_eq!(clicks(&replay_app), vec![Click(1)]);
+}
@@ -0,0 +1,118 @@
+#![cfg(feature = "testing")]
+
+use bevy::prelude::*;
+use leptos::prelude::{GetUntracked, Owner, Set};
+use leptos_bevy_canvas::prelude::*;
+use leptos_bevy_canvas::systems::ResourceFieldSync;
+use leptos_bevy_canvas::testing::BevyTestApp;
+
+#[derive(Resource, Debug, PartialEq)]
+struct Settings {
+    volume: f32,
+    brightness: f32,
+}
+
+#[derive(Resource, Default)]
+struct Changes(u32);
+
+fn count_changes(settings: Res<Settings>, mut changes: ResMut<Changes>) {
+    if settings.is_changed() {
+        changes.0 += 1;
+    }
+}
+
+fn test_app() -> (BevyTestApp, RwSignalSynced<f32>, RwSignalSynced<f32>) {
+    let mut test_app = BevyTestApp::new();
+
+    let (volume, bevy_volume) = test_app.leptos(|| signal_synced(0.5));
+    let (brightness, bevy_brightness) = test_app.leptos(|| signal_synced(1.0));
+
+    test_app
+        .app_mut()
+        .insert_resource(Settings {
+            volume: 0.5,
+            brightness: 1.0,
+        })
+        .init_resource::<Changes>()
+        .sync_leptos_signal_with_resource_field(
+            bevy_volume,
+            |settings: &Settings| &settings.volume,
+            |settings: &mut Settings| &mut settings.volume,
+        )
+        .sync_leptos_signal_with_resource_field(
+            bevy_brightness,
+            |settings: &Settings| &settings.brightness,
+            |settings: &mut Settings| &mut settings.brightness,
+        )
+        .add_systems(Update, count_changes);
+
+    test_app.update_n(2);
+
+    (test_app, volume, brightness)
+}
+
+#[test]
+fn fields_of_the_same_type_sync_separately() {
+    let (mut test_app, volume, brightness) = test_app();
+
+    volume.set(0.2);
+    test_app.update();
+
+    assert_eq!(
+        test_app.resource::<Settings>(),
+        &Settings {
+            volume: 0.2,
+            brightness: 1.0
+        }
+    );
+
+    test_app.world_mut().resource_mut::<Settings>().brightness = 0.7;
+    test_app.update();
+
+    assert_eq!(volume.get_untracked(), 0.2);
+    assert_eq!(brightness.get_untracked(), 0.7);
+}
+
+#[test]
+fn unchanged_field_does_not_mark_the_resource_changed() {
+    let (mut test_app, volume, _) = test_app();
+
+    let changes = test_app.resource::<Changes>().0;
+
+    volume.set(0.5);
+    test_app.update();
+    assert_eq!(test_app.resource::<Changes>().0, changes);
+
+    volume.set(0.3);
+    test_app.update();
+    assert_eq!(test_app.resource::<Changes>().0, changes + 1);
+}
+
+type VolumeSync =
+    ResourceFieldSync<BevyMessageDuplex<f32>, fn(&Settings) -> &f32, fn(&mut Settings) -> &mut f32>;
+
+#[test]
+fn field_sync_is_removed_when_the_signal_is_disposed() {
+    let mut test_app = BevyTestApp::new();
+
+    let child = test_app.leptos(Owner::new);
+    let (_, bevy_volume) = child.with(|| signal_synced(0.5));
+
+    test_app
+        .app_mut()
+        .insert_resource(Settings {
+            volume: 0.5,
+            brightness: 1.0,
+        })
+        .sync_leptos_signal_with_resource_field(
+            bevy_volume,
+            (|settings| &settings.volume) as fn(&Settings) -> &f32,
+            (|settings| &mut settings.volume) as fn(&mut Settings) -> &mut f32,
+        );
+    test_app.update();
+    assert!(test_app.world().contains_resource::<VolumeSync>());
+
+    child.cleanup();
+    test_app.update();
+    assert!(!test_app.world().contains_resource::<VolumeSync>());
+}
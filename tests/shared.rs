@@ -0,0 +1,23 @@
+#![cfg(feature = "testing")]
+
+use leptos_bevy_canvas::prelude::*;
+
+#[test]
+fn mutation_only_clones_while_shared() {
+    let mut value = Shared::new(vec![1, 2, 3]);
+    let sent = value.clone();
+
+    assert!(Shared::ptr_eq(&value, &sent));
+
+    value.push(4);
+
+    assert!(!Shared::ptr_eq(&value, &sent));
+    assert_eq!(*sent, vec![1, 2, 3]);
+    assert_eq!(*value, vec![1, 2, 3, 4]);
+
+    let allocation = std::ptr::from_ref::<Vec<i32>>(&value);
+
+    value.push(5);
+
+    assert_eq!(std::ptr::from_ref::<Vec<i32>>(&value), allocation);
+}
@@ -0,0 +1,135 @@
+#![cfg(feature = "testing")]
+
+use bevy::prelude::*;
+use leptos::prelude::{GetUntracked, RwSignal, Set, Update};
+use leptos_bevy_canvas::prelude::*;
+use leptos_bevy_canvas::testing::BevyTestApp;
+use leptos_bevy_canvas::traits::{HasReceiver, HasSender};
+
+#[derive(Resource, Clone, Debug, PartialEq)]
+struct Score(u32);
+
+#[derive(Resource, Clone, Debug, PartialEq)]
+struct Settings {
+    name: String,
+    volume: u32,
+}
+
+/// The values Leptos has sent to Bevy since the last call
+fn sent<T>(duplex: &BevyMessageDuplex<T>) -> Vec<T> {
+    duplex.rx().try_iter().collect()
+}
+
+#[test]
+fn writes_of_a_frame_are_sent_once() {
+    let mut test_app = BevyTestApp::new();
+
+    let (score, bevy_score) = test_app.leptos(|| signal_synced(Score(0)));
+    assert_eq!(sent(&bevy_score), vec![Score(0)]);
+
+    score.set(Score(1));
+    score.update(|score| score.0 += 1);
+    score.set(Score(3));
+
+    // nothing is sent before the next frame
+    assert_eq!(sent(&bevy_score), vec![]);
+
+    test_app.run_leptos_frame();
+    assert_eq!(sent(&bevy_score), vec![Score(3)]);
+
+    test_app.run_leptos_frame();
+    assert_eq!(sent(&bevy_score), vec![]);
+}
+
+#[test]
+fn slice_is_synced_both_ways() {
+    let mut test_app = BevyTestApp::new();
+
+    let (settings, volume, set_volume, bevy_settings) = test_app.leptos(|| {
+        let (settings, bevy_settings) = signal_synced(Settings {
+            name: "Player".to_string(),
+            volume: 5,
+        });
+
+        let (volume, set_volume) = settings.slice(
+            |settings| settings.volume,
+            |settings, volume| settings.volume = volume,
+        );
+
+        (settings, volume, set_volume, bevy_settings)
+    });
+
+    test_app
+        .app_mut()
+        .sync_leptos_signal_with_resource(bevy_settings);
+    test_app.update();
+
+    set_volume.set(7);
+    test_app.update();
+
+    assert_eq!(
+        test_app.resource::<Settings>(),
+        &Settings {
+            name: "Player".to_string(),
+            volume: 7,
+        }
+    );
+
+    test_app.world_mut().resource_mut::<Settings>().volume = 2;
+    test_app.update();
+
+    assert_eq!(volume.get_untracked(), 2);
+    assert_eq!(settings.get_untracked().volume, 2);
+}
+
+#[test]
+fn existing_signal_is_synced() {
+    let mut test_app = BevyTestApp::new();
+
+    let (score, bevy_score) = test_app.leptos(|| {
+        let score = RwSignal::new(Score(0));
+        (score, sync_signal(score))
+    });
+
+    test_app
+        .app_mut()
+        .sync_leptos_signal_with_resource(bevy_score);
+    test_app.update();
+
+    assert_eq!(test_app.resource::<Score>(), &Score(0));
+
+    score.set(Score(1));
+    score.set(Score(2));
+    test_app.update();
+
+    assert_eq!(test_app.resource::<Score>(), &Score(2));
+
+    test_app.world_mut().resource_mut::<Score>().0 = 3;
+    test_app.update();
+
+    assert_eq!(score.get_untracked(), Score(3));
+}
+
+#[test]
+fn values_from_bevy_are_not_sent_back() {
+    let mut test_app = BevyTestApp::new();
+
+    let (score, bevy_score) = test_app.leptos(|| {
+        let score = RwSignal::new(Score(0));
+        (score, sync_signal(score))
+    });
+    assert_eq!(sent(&bevy_score), vec![Score(0)]);
+
+    bevy_score.tx().send(Score(4)).unwrap();
+    test_app.run_leptos_frame();
+    test_app.run_leptos_frame();
+
+    assert_eq!(score.get_untracked(), Score(4));
+    assert_eq!(sent(&bevy_score), vec![]);
+
+    // writes in Leptos are still sent afterwards
+    score.set(Score(5));
+    test_app.run_leptos_frame();
+
+    assert_eq!(sent(&bevy_score), vec![Score(5)]);
+}
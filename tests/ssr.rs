@@ -0,0 +1,45 @@
+#![cfg(feature = "ssr")]
+
+use bevy::prelude::*;
+use leptos::prelude::*;
+use leptos_bevy_canvas::prelude::*;
+
+#[derive(Message, Clone, Debug, PartialEq)]
+struct Clicked(u32);
+
+#[test]
+fn canvas_is_rendered_without_starting_bevy() {
+    let owner = Owner::new();
+
+    let html = owner.with(|| {
+        view! { <BevyCanvas init=|| panic!("Bevy must not be started on the server") /> }.to_html()
+    });
+
+    assert!(html.contains(r#"<canvas id="bevy_canvas">"#), "{html}");
+}
+
+#[test]
+fn bridges_are_inert_on_the_server() {
+    let owner = Owner::new();
+
+    owner.with(|| {
+        let (clicked, _bevy_clicked) = message_l2b::<Clicked>();
+
+        // nothing reads on the server but sending still succeeds
+        for click in 0..100 {
+            clicked.send(Clicked(click)).unwrap();
+        }
+    });
+}
+
+#[test]
+fn shared_signal_starts_with_the_initial_value() {
+    let owner = Owner::new();
+
+    let score = owner.with(|| {
+        let (score, _bevy_score) = shared_signal_synced(|| 5_u32);
+        score
+    });
+
+    assert_eq!(score.get_untracked(), 5);
+}
@@ -0,0 +1,181 @@
+#![cfg(all(feature = "testing", feature = "bevy_state"))]
+
+use bevy::prelude::*;
+use leptos::prelude::{GetUntracked, Set};
+use leptos_bevy_canvas::prelude::*;
+use leptos_bevy_canvas::testing::BevyTestApp;
+use std::sync::{Arc, Mutex};
+
+#[derive(States, Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+enum Phase {
+    #[default]
+    Menu,
+    Playing,
+    Paused,
+}
+
+#[derive(Resource, Clone, Debug, PartialEq)]
+struct Score(u32);
+
+fn set_phase(test_app: &mut BevyTestApp, phase: Phase) {
+    test_app
+        .world_mut()
+        .resource_mut::<NextState<Phase>>()
+        .set(phase);
+    // the transition runs after `PreUpdate`, so the sync sees it in the next frame
+    test_app.update_n(2);
+}
+
+#[test]
+fn pending_bevy_transition_wins_with_bevy_wins_policy() {
+    let mut test_app = BevyTestApp::new();
+
+    let (phase, bevy_phase) = test_app.leptos(|| signal_synced(Phase::Menu));
+
+    test_app
+        .app_mut()
+        .init_state::<Phase>()
+        .sync_leptos_signal_with_state_with_options(
+            bevy_phase,
+            SyncOptions::default().policy(SyncPolicy::BevyWins),
+        );
+    test_app.update();
+
+    // Bevy requests a transition at the end of a frame that is applied in the next one
+    test_app
+        .world_mut()
+        .resource_mut::<NextState<Phase>>()
+        .set(Phase::Playing);
+
+    // Leptos writes in between
+    phase.set(Phase::Paused);
+    test_app.update();
+
+    assert_eq!(test_app.state::<Phase>(), &Phase::Playing);
+    assert_eq!(phase.get_untracked(), Phase::Playing);
+
+    test_app.update();
+    assert_eq!(test_app.state::<Phase>(), &Phase::Playing);
+    assert_eq!(phase.get_untracked(), Phase::Playing);
+}
+
+#[test]
+fn leptos_write_wins_over_pending_transition_with_leptos_wins_policy() {
+    let mut test_app = BevyTestApp::new();
+
+    let (phase, bevy_phase) = test_app.leptos(|| signal_synced(Phase::Menu));
+
+    test_app
+        .app_mut()
+        .init_state::<Phase>()
+        .sync_leptos_signal_with_state(bevy_phase);
+    test_app.update();
+
+    test_app
+        .world_mut()
+        .resource_mut::<NextState<Phase>>()
+        .set(Phase::Playing);
+
+    phase.set(Phase::Paused);
+    test_app.update_n(2);
+
+    assert_eq!(test_app.state::<Phase>(), &Phase::Paused);
+    assert_eq!(phase.get_untracked(), Phase::Paused);
+}
+
+#[test]
+fn every_transition_is_received() {
+    let mut test_app = BevyTestApp::new();
+
+    let received = Arc::new(Mutex::new(Vec::new()));
+
+    let (transitions, bevy_transitions) = test_app.leptos(|| {
+        let (transitions, bevy_transitions) = message_b2l::<StateTransitionEvent<Phase>>();
+
+        let received = Arc::clone(&received);
+        transitions.on_message(move |transition| {
+            received.lock().unwrap().push(transition.entered);
+        });
+
+        (transitions, bevy_transitions)
+    });
+
+    test_app
+        .app_mut()
+        .init_state::<Phase>()
+        .export_state_transitions_to_leptos(bevy_transitions);
+    test_app.update();
+    received.lock().unwrap().clear();
+
+    // several Bevy frames run before Leptos gets to apply them
+    for phase in [Phase::Playing, Phase::Paused, Phase::Menu] {
+        test_app
+            .world_mut()
+            .resource_mut::<NextState<Phase>>()
+            .set(phase);
+        test_app.app_mut().update();
+    }
+    test_app.run_leptos_frame();
+
+    assert_eq!(
+        *received.lock().unwrap(),
+        vec![Some(Phase::Playing), Some(Phase::Paused), Some(Phase::Menu)]
+    );
+    assert_eq!(
+        transitions
+            .get_untracked()
+            .and_then(|transition| transition.entered),
+        Some(Phase::Menu)
+    );
+}
+
+#[test]
+#[should_panic(expected = "`SyncOptions::send_schedule` isn't supported by \
+                           `sync_leptos_signal_with_state`")]
+fn state_sync_rejects_a_send_schedule() {
+    let mut test_app = BevyTestApp::new();
+
+    let (_phase, bevy_phase) = test_app.leptos(|| signal_synced(Phase::Menu));
+
+    test_app
+        .app_mut()
+        .init_state::<Phase>()
+        .sync_leptos_signal_with_state_with_options(
+            bevy_phase,
+            SyncOptions::default().send_schedule(Last),
+        );
+}
+
+#[test]
+fn sync_is_paused_outside_of_its_state() {
+    let mut test_app = BevyTestApp::new();
+
+    let (score, bevy_score) = test_app.leptos(|| signal_synced(Score(0)));
+
+    test_app
+        .app_mut()
+        .init_state::<Phase>()
+        .sync_leptos_signal_with_resource_with_options(
+            bevy_score,
+            SyncOptions::default().in_state(Phase::Playing),
+        );
+    test_app.update();
+
+    // Leptos writes wait until the state is entered
+    score.set(Score(1));
+    test_app.update();
+    assert_eq!(test_app.resource::<Score>(), &Score(0));
+
+    set_phase(&mut test_app, Phase::Playing);
+    assert_eq!(test_app.resource::<Score>(), &Score(1));
+
+    set_phase(&mut test_app, Phase::Paused);
+
+    // as do Bevy changes
+    test_app.world_mut().resource_mut::<Score>().0 = 2;
+    test_app.update();
+    assert_eq!(score.get_untracked(), Score(1));
+
+    set_phase(&mut test_app, Phase::Playing);
+    assert_eq!(score.get_untracked(), Score(2));
+}
@@ -0,0 +1,163 @@
+#![cfg(feature = "testing")]
+
+use bevy::prelude::*;
+use leptos::prelude::Set;
+use leptos_bevy_canvas::prelude::*;
+use leptos_bevy_canvas::testing::BevyTestApp;
+
+#[derive(Message, Clone, Debug, PartialEq)]
+struct Clicked(u32);
+
+#[derive(Resource, Clone, Debug, Default, PartialEq)]
+struct Tool(u32);
+
+/// The clicks received per frame together with the tool in that frame
+#[derive(Resource, Default)]
+struct Frames(Vec<(Vec<u32>, u32)>);
+
+fn collect_clicks(
+    mut clicked: MessageReader<Clicked>,
+    tool: Res<Tool>,
+    mut frames: ResMut<Frames>,
+) {
+    let clicks = clicked.read().map(|click| click.0).collect::<Vec<_>>();
+
+    if !clicks.is_empty() {
+        frames.0.push((clicks, tool.0));
+    }
+}
+
+fn test_app() -> (
+    BevyTestApp,
+    LeptosMessageSender<Clicked>,
+    RwSignalSynced<Tool>,
+) {
+    let mut test_app = BevyTestApp::new();
+
+    let ((clicked, bevy_clicked), (tool, bevy_tool)) =
+        test_app.leptos(|| (message_l2b::<Clicked>(), signal_synced(Tool::default())));
+
+    test_app
+        .app_mut()
+        .import_message_from_leptos(bevy_clicked)
+        .sync_leptos_signal_with_resource(bevy_tool)
+        .init_resource::<Frames>()
+        .add_systems(Update, collect_clicks);
+    test_app.update();
+
+    (test_app, clicked, tool)
+}
+
+#[test]
+fn update_systems_see_the_whole_transaction() {
+    let (mut test_app, clicked, tool) = test_app();
+
+    test_app.leptos(|| {
+        bevy_transaction(|| {
+            tool.set(Tool(1));
+            clicked.send(Clicked(1)).unwrap();
+        })
+    });
+    test_app.update();
+
+    assert_eq!(test_app.resource::<Frames>().0, vec![(vec![1], 1)]);
+}
+
+#[test]
+fn transaction_is_sent_as_a_whole_and_not_overtaken() {
+    let (mut test_app, clicked, tool) = test_app();
+
+    for click in 0..49 {
+        clicked.send(Clicked(click)).unwrap();
+    }
+
+    // only one more message fits so nothing of the transaction is sent yet
+    test_app.leptos(|| {
+        bevy_transaction(|| {
+            tool.set(Tool(1));
+            clicked.send(Clicked(100)).unwrap();
+            clicked.send(Clicked(101)).unwrap();
+        })
+    });
+
+    // queued behind the transaction
+    clicked.send(Clicked(102)).unwrap();
+
+    test_app.update();
+    assert_eq!(
+        test_app.resource::<Frames>().0,
+        vec![((0..49).collect(), 0)]
+    );
+
+    test_app.update();
+    assert_eq!(test_app.resource::<Frames>().0[1], (vec![100, 101, 102], 1));
+}
+
+#[test]
+fn transaction_larger_than_its_channel_is_split() {
+    let (mut test_app, clicked, tool) = test_app();
+
+    test_app.leptos(|| {
+        bevy_transaction(|| {
+            tool.set(Tool(1));
+
+            for click in 0..60 {
+                clicked.send(Clicked(click)).unwrap();
+            }
+        })
+    });
+
+    test_app.update();
+    assert_eq!(
+        test_app.resource::<Frames>().0,
+        vec![((0..50).collect(), 1)]
+    );
+
+    test_app.update();
+    assert_eq!(test_app.resource::<Frames>().0[1], ((50..60).collect(), 1));
+
+    // nothing is stuck afterwards
+    clicked.send(Clicked(100)).unwrap();
+    test_app.update();
+    assert_eq!(test_app.resource::<Frames>().0[2], (vec![100], 1));
+}
+
+#[test]
+fn waiting_transaction_only_holds_up_its_own_channels() {
+    let (mut test_app, clicked, tool) = test_app();
+
+    for click in 0..50 {
+        clicked.send(Clicked(click)).unwrap();
+    }
+
+    test_app.leptos(|| bevy_transaction(|| clicked.send(Clicked(100)).unwrap()));
+
+    // the tool doesn't wait for the clicks of the transaction
+    test_app.leptos(|| tool.set(Tool(2)));
+    test_app.update();
+
+    assert_eq!(
+        test_app.resource::<Frames>().0,
+        vec![((0..50).collect(), 2)]
+    );
+
+    test_app.update();
+    assert_eq!(test_app.resource::<Frames>().0[1], (vec![100], 2));
+}
+
+#[test]
+fn sends_behind_a_waiting_transaction_are_limited() {
+    let (_test_app, clicked, _tool) = test_app();
+
+    for click in 0..50 {
+        clicked.send(Clicked(click)).unwrap();
+    }
+
+    bevy_transaction(|| clicked.send(Clicked(100)).unwrap());
+
+    for click in 0..999 {
+        clicked.send(Clicked(click)).unwrap();
+    }
+
+    assert!(clicked.send(Clicked(1000)).is_err());
+}